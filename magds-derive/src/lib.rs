@@ -0,0 +1,90 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Error, Fields, Lit, Meta, NestedMeta
+};
+
+#[proc_macro_derive(MagdsRecord, attributes(magds))]
+pub fn derive_magds_record(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into()
+    }
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let name = &input.ident;
+    let parent_id = attribute_value(&input.attrs, "parent")?
+        .unwrap_or_else(|| name.to_string());
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(name, "MagdsRecord requires named fields"))
+        },
+        _ => return Err(Error::new_spanned(name, "MagdsRecord can only be derived for structs"))
+    };
+
+    let mut field_values = Vec::new();
+    let mut field_inits = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let sensor = attribute_value(&field.attrs, "sensor")?
+            .unwrap_or_else(|| ident.to_string());
+
+        field_values.push(quote! {
+            (
+                ::std::rc::Rc::from(#sensor),
+                <#ty as ::magds::simple::record::RecordField>::data_type(),
+                ::magds::simple::record::RecordField::values(&self.#ident)
+            )
+        });
+        field_inits.push(quote! {
+            #ident: <#ty as ::magds::simple::record::RecordField>::from_record_value(
+                record.get(#sensor)
+            )?
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::magds::simple::record::MagdsRecord for #name #ty_generics
+        #where_clause
+        {
+            fn parent_id() -> ::std::rc::Rc<str> { ::std::rc::Rc::from(#parent_id) }
+
+            fn fields(&self) -> ::std::vec::Vec<::magds::simple::record::RecordFieldValues> {
+                vec![#(#field_values),*]
+            }
+
+            fn from_values(record: &::magds::simple::record::Record) -> Option<Self> {
+                Some(#name { #(#field_inits),* })
+            }
+        }
+    })
+}
+
+fn attribute_value(attrs: &[Attribute], key: &str) -> Result<Option<String>, Error> {
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("magds")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new_spanned(meta, "expected #[magds(key = \"value\")]"))
+        };
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(name_value)) => {
+                    if !name_value.path.is_ident(key) { continue }
+                    match name_value.lit {
+                        Lit::Str(value) => return Ok(Some(value.value())),
+                        lit => return Err(Error::new_spanned(lit, "expected string literal"))
+                    }
+                }
+                nested => return Err(Error::new_spanned(nested, "expected key = \"value\""))
+            }
+        }
+    }
+    Ok(None)
+}
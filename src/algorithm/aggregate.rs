@@ -0,0 +1,238 @@
+use std::{
+    collections::HashSet,
+    iter,
+    rc::Rc
+};
+
+use polars::prelude::{ DataFrame, Series, NamedFrom };
+
+use bionet_common::data::{ DataCategory, DataTypeValue, DataTypeValueStr };
+
+use crate::simple::{ fuzzy, magds::MAGDS, parser::RowValue };
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    Count,
+    Sum,
+    Mean,
+    Min,
+    Max,
+    Distinct
+}
+
+impl Aggregation {
+    fn name(&self) -> &'static str {
+        match self {
+            Aggregation::Count => "count",
+            Aggregation::Sum => "sum",
+            Aggregation::Mean => "mean",
+            Aggregation::Min => "min",
+            Aggregation::Max => "max",
+            Aggregation::Distinct => "distinct"
+        }
+    }
+
+    fn is_numerical(&self) -> bool {
+        matches!(
+            self, Aggregation::Sum | Aggregation::Mean | Aggregation::Min | Aggregation::Max
+        )
+    }
+}
+
+struct Group {
+    key: Rc<str>,
+    count: u64,
+    values: Vec<DataTypeValue>
+}
+
+pub fn aggregate(
+    magds: &MAGDS, group_by: Rc<str>, aggregated: Rc<str>, aggregations: &[Aggregation]
+) -> Result<DataFrame, String> {
+    let group_sensor = magds.sensor(group_by.clone())
+        .ok_or(format!("sensor {group_by} doesn't exists"))?;
+    let data_category = magds.sensor_data_category(aggregated.clone())
+        .ok_or(format!("sensor {aggregated} doesn't exists"))?;
+    let data_type = magds.sensor_data_type(aggregated.clone())
+        .ok_or(format!("sensor {aggregated} doesn't exists"))?;
+    if matches!(data_category, DataCategory::Categorical) {
+        if let Some(aggregation) = aggregations.iter().find(|a| a.is_numerical()) {
+            return Err(format!(
+                "cannot compute {} of categorical sensor {aggregated}", aggregation.name()
+            ))
+        }
+    }
+
+    let mut groups: Vec<Group> = Vec::new();
+    for (element, neurons) in group_sensor.borrow().elements() {
+        let mut group = Group { key: element.borrow().id().id, count: 0, values: Vec::new() };
+        for neuron_id in neurons.keys() {
+            let neuron = match magds.neuron_from_id(neuron_id) {
+                Some(neuron) => neuron,
+                None => continue
+            };
+            let neuron = neuron.borrow();
+            group.count += neuron.counter() as u64;
+            for id in neuron.explain().into_keys().filter(|id| id.parent_id == aggregated) {
+                let value = match DataTypeValueStr(&id.id).data_type_value(data_type.clone()) {
+                    Some(value) => value,
+                    None => {
+                        log::warn!("cannot convert {} to {:?}, skipping", id.id, data_type);
+                        continue
+                    }
+                };
+                group.values.extend(iter::repeat(value).take(neuron.counter()));
+            }
+        }
+        groups.push(group);
+    }
+
+    let mut columns = vec![
+        Series::new(&group_by, groups.iter().map(|g| g.key.to_string()).collect::<Vec<_>>())
+    ];
+    for aggregation in aggregations {
+        let name = format!("{aggregated}_{}", aggregation.name());
+        let series = match aggregation {
+            Aggregation::Count => {
+                Series::new(&name, groups.iter().map(|g| g.count).collect::<Vec<_>>())
+            }
+            Aggregation::Distinct => {
+                let distinct: Vec<u64> = groups.iter()
+                    .map(|g| g.values.iter().filter_map(RowValue::new).collect::<HashSet<_>>())
+                    .map(|values| values.len() as u64)
+                    .collect();
+                Series::new(&name, distinct)
+            }
+            _ => {
+                let values: Vec<Option<f64>> = groups.iter()
+                    .map(|g| numerical_aggregate(*aggregation, &g.values))
+                    .collect();
+                Series::new(&name, values)
+            }
+        };
+        columns.push(series);
+    }
+
+    DataFrame::new(columns).map_err(|e| e.to_string())
+}
+
+fn numerical_aggregate(aggregation: Aggregation, values: &[DataTypeValue]) -> Option<f64> {
+    let values: Vec<f64> = values.iter().filter_map(fuzzy::numeric_value).collect();
+    if values.is_empty() { return None }
+    match aggregation {
+        Aggregation::Sum => Some(values.iter().sum()),
+        Aggregation::Mean => Some(values.iter().sum::<f64>() / values.len() as f64),
+        Aggregation::Min => values.iter().cloned().reduce(f64::min),
+        Aggregation::Max => values.iter().cloned().reduce(f64::max),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use bionet_common::{ neuron::NeuronID, data::DataType };
+
+    use crate::simple::{
+        magds::MAGDS,
+        parser::{ self, ParserOptions }
+    };
+
+    use super::Aggregation;
+
+    #[test]
+    fn aggregate() {
+        let magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let aggregations = [
+            Aggregation::Count, Aggregation::Sum, Aggregation::Mean,
+            Aggregation::Min, Aggregation::Max, Aggregation::Distinct
+        ];
+
+        let df = super::aggregate(
+            &magds, "variety".into(), "petal.length".into(), &aggregations
+        ).unwrap();
+        println!("{df}");
+        assert_eq!(df.shape(), (3, 7));
+
+        let groups: Vec<&str> = df.column("variety").unwrap().utf8().unwrap()
+            .into_no_null_iter()
+            .collect();
+        let setosa = groups.iter().position(|g| *g == "Setosa").unwrap();
+        let count = df.column("petal.length_count").unwrap().u64().unwrap().get(setosa);
+        assert_eq!(count, Some(49));
+
+        let column = |name: &str| df.column(name).unwrap().f64().unwrap().get(setosa).unwrap();
+        let (min, mean, max) = (
+            column("petal.length_min"), column("petal.length_mean"), column("petal.length_max")
+        );
+        assert!(min <= mean && mean <= max);
+        assert!(max < 2.0);
+
+        let df = super::aggregate(
+            &magds, "variety".into(), "variety".into(), &[Aggregation::Mean]
+        );
+        assert!(df.is_err());
+        let df = super::aggregate(
+            &magds, "unknown".into(), "variety".into(), &[Aggregation::Count]
+        );
+        assert!(df.is_err());
+    }
+
+    #[test]
+    fn aggregate_collapsed() {
+        let aggregations = [Aggregation::Count, Aggregation::Mean];
+        let magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let options = ParserOptions { collapse_duplicates: true };
+        let collapsed = parser::magds_from_csv_with("iris", "data/iris.csv", &options).unwrap();
+        assert_eq!(collapsed.neurons.len(), magds.neurons.len() - 1);
+
+        let results = [&magds, &collapsed].map(|magds| {
+            let df = super::aggregate(
+                magds, "variety".into(), "petal.length".into(), &aggregations
+            ).unwrap();
+            let groups: Vec<String> = df.column("variety").unwrap().utf8().unwrap()
+                .into_no_null_iter()
+                .map(|group| group.to_string())
+                .collect();
+            let counts: Vec<u64> = df.column("petal.length_count").unwrap().u64().unwrap()
+                .into_no_null_iter()
+                .collect();
+            let means: Vec<f64> = df.column("petal.length_mean").unwrap().f64().unwrap()
+                .into_no_null_iter()
+                .collect();
+            let mut rows: Vec<(String, u64, f64)> = groups.into_iter()
+                .zip(counts)
+                .zip(means)
+                .map(|((group, count), mean)| (group, count, mean))
+                .collect();
+            rows.sort_by(|a, b| a.0.cmp(&b.0));
+            rows
+        });
+
+        for (row, collapsed_row) in results[0].iter().zip(&results[1]) {
+            assert_eq!(row.0, collapsed_row.0);
+            assert_eq!(row.1, collapsed_row.1);
+            assert!((row.2 - collapsed_row.2).abs() < 1e-9);
+        }
+        assert_eq!(results[1].iter().map(|row| row.1).sum::<u64>(), 150);
+    }
+
+    #[test]
+    fn aggregate_typed() {
+        let mut magds = MAGDS::new();
+        magds.create_sensor("group".into(), DataType::String).unwrap();
+        magds.create_sensor("value".into(), DataType::I16).unwrap();
+        for (id, value) in [("1", -300_i16), ("2", 200), ("3", 200)] {
+            let neuron = magds.create_neuron(NeuronID::new(id, "rows")).unwrap();
+            magds.sensor_insert_connected("group".into(), &"g".to_string().into(), &neuron)
+                .unwrap();
+            magds.sensor_insert_connected("value".into(), &value.into(), &neuron).unwrap();
+        }
+
+        let aggregations = [Aggregation::Sum, Aggregation::Min, Aggregation::Distinct];
+        let df = super::aggregate(&magds, "group".into(), "value".into(), &aggregations).unwrap();
+        assert_eq!(df.column("value_sum").unwrap().f64().unwrap().get(0), Some(100.0));
+        assert_eq!(df.column("value_min").unwrap().f64().unwrap().get(0), Some(-300.0));
+        assert_eq!(df.column("value_distinct").unwrap().u64().unwrap().get(0), Some(2));
+    }
+}
@@ -0,0 +1,118 @@
+use std::rc::Rc;
+
+use ordered_float::OrderedFloat;
+
+use bionet_common::neuron::NeuronID;
+
+use crate::simple::magds::MAGDS;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnomalyScore {
+    pub neuron: NeuronID,
+    pub score: f64,
+    pub rarity: f64,
+    pub similarity: f64
+}
+
+pub fn anomaly_scores(magds: &mut MAGDS, parent_id: Rc<str>) -> Vec<AnomalyScore> {
+    let neuron_ids: Vec<NeuronID> = magds.neurons.keys()
+        .filter(|id| id.parent_id == parent_id)
+        .cloned()
+        .collect();
+    let records_count = magds.records_count(&parent_id) as f64;
+    let element_records = magds.element_records(&parent_id);
+
+    let mut scores: Vec<AnomalyScore> = Vec::new();
+    for neuron_id in neuron_ids {
+        let neuron = magds.neurons[&neuron_id].clone();
+        let elements = neuron.borrow().explain();
+        if elements.is_empty() {
+            scores.push(AnomalyScore {
+                neuron: neuron_id, score: 1.0, rarity: 1.0, similarity: 0.0
+            });
+            continue
+        }
+
+        let rarity = elements.keys()
+            .map(|id| element_rarity(element_records[id] as f64, records_count))
+            .sum::<f64>() / elements.len() as f64;
+
+        let activated = magds.activate_neuron_elements(&neuron_id, 1.0_f32, true)
+            .unwrap_or_default();
+        let self_activation = neuron.borrow().activation() as f64;
+        let best_other = activated.iter()
+            .filter(|(id, _)| **id != neuron_id && id.parent_id == parent_id)
+            .map(|(_, other)| OrderedFloat(other.borrow().activation() as f64))
+            .max()
+            .map(|activation| activation.0)
+            .unwrap_or(0.0);
+        magds.deactivate();
+
+        let similarity = if self_activation == 0.0 {
+            0.0
+        } else {
+            (best_other / self_activation).clamp(0.0, 1.0)
+        };
+
+        scores.push(AnomalyScore {
+            neuron: neuron_id,
+            score: (rarity + (1.0 - similarity)) / 2.0,
+            rarity,
+            similarity
+        });
+    }
+
+    scores.sort_by(|a, b| OrderedFloat(b.score).cmp(&OrderedFloat(a.score)));
+    scores
+}
+
+fn element_rarity(records: f64, records_count: f64) -> f64 {
+    if records_count <= 1.0 { return 0.0 }
+    let frequency = (records / records_count).clamp(f64::MIN_POSITIVE, 1.0);
+    (-frequency.ln() / records_count.ln()).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use test_log::test;
+
+    use bionet_common::{
+        neuron::NeuronID,
+        data::DataTypeValue
+    };
+
+    use crate::simple::parser;
+
+    #[test]
+    fn anomaly_scores() {
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let neurons_count = magds.neurons.len();
+
+        let outlier = magds.create_neuron(NeuronID::new("outlier", "iris")).unwrap();
+        let values: Vec<(Rc<str>, DataTypeValue)> = vec![
+            ("sepal.length".into(), 12.5_f64.into()),
+            ("sepal.width".into(), 0.1_f64.into()),
+            ("petal.length".into(), 9.9_f64.into()),
+            ("petal.width".into(), 4.2_f64.into()),
+            ("variety".into(), "Unknown".to_string().into())
+        ];
+        for (sensor_id, value) in &values {
+            magds.sensor_insert_connected(sensor_id.clone(), value, &outlier).unwrap();
+        }
+
+        let scores = super::anomaly_scores(&mut magds, "iris".into());
+        assert_eq!(scores.len(), neurons_count + 1);
+        assert_eq!(scores[0].neuron, NeuronID::new("outlier", "iris"));
+        for window in scores.windows(2) { assert!(window[0].score >= window[1].score); }
+        for score in &scores {
+            assert!(score.score >= 0.0 && score.score <= 1.0);
+            assert!(score.rarity >= 0.0 && score.rarity <= 1.0);
+            assert!(score.similarity >= 0.0 && score.similarity <= 1.0);
+        }
+
+        let scores = super::anomaly_scores(&mut magds, "unknown".into());
+        assert!(scores.is_empty());
+    }
+}
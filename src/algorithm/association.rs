@@ -0,0 +1,280 @@
+use std::{
+    collections::HashMap,
+    rc::Rc
+};
+
+use ordered_float::OrderedFloat;
+use polars::prelude::{ DataFrame, Series, NamedFrom };
+
+use bionet_common::{
+    neuron::NeuronID,
+    data::DataCategory
+};
+
+use crate::simple::magds::MAGDS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Correlation {
+    #[default]
+    Pearson,
+    Spearman
+}
+
+enum SensorValues {
+    Numerical(HashMap<NeuronID, Vec<f64>>),
+    Categorical(HashMap<NeuronID, Vec<Rc<str>>>)
+}
+
+impl SensorValues {
+    fn new(magds: &MAGDS, id: &Rc<str>) -> Option<SensorValues> {
+        let sensor = magds.sensor(id.clone())?;
+        let numerical = matches!(
+            magds.sensor_data_category(id.clone())?, DataCategory::Numerical
+        );
+
+        let mut values: HashMap<NeuronID, Vec<Rc<str>>> = HashMap::new();
+        for (element, neurons) in sensor.borrow().elements() {
+            let value = element.borrow().id().id;
+            for neuron_id in neurons.into_keys() {
+                values.entry(neuron_id).or_default().push(value.clone());
+            }
+        }
+
+        if numerical {
+            Some(SensorValues::Numerical(
+                values.into_iter()
+                    .map(|(id, values)| {
+                        let values = values.iter()
+                            .filter_map(|value| value.parse::<f64>().ok())
+                            .collect();
+                        (id, values)
+                    })
+                    .collect()
+            ))
+        } else {
+            Some(SensorValues::Categorical(values))
+        }
+    }
+}
+
+pub fn association_matrix(magds: &MAGDS, correlation: Correlation) -> Result<DataFrame, String> {
+    let mut sensor_ids: Vec<Rc<str>> = magds.sensors.keys().cloned().collect();
+    sensor_ids.sort();
+
+    let values: Vec<SensorValues> = sensor_ids.iter()
+        .map(|id| SensorValues::new(magds, id).ok_or(format!("sensor {id} doesn't exists")))
+        .collect::<Result<_, _>>()?;
+
+    let mut columns = vec![
+        Series::new("sensor", sensor_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>())
+    ];
+    for (j, id) in sensor_ids.iter().enumerate() {
+        let column: Vec<Option<f64>> = (0..sensor_ids.len())
+            .map(|i| {
+                if i == j { return Some(1.0) }
+                association(&values[i], &values[j], correlation)
+            })
+            .collect();
+        columns.push(Series::new(id, column));
+    }
+
+    DataFrame::new(columns).map_err(|e| e.to_string())
+}
+
+fn association(a: &SensorValues, b: &SensorValues, correlation: Correlation) -> Option<f64> {
+    match (a, b) {
+        (SensorValues::Numerical(a), SensorValues::Numerical(b)) => {
+            let (x, y): (Vec<f64>, Vec<f64>) = value_pairs(a, b).into_iter()
+                .map(|(x, y)| (*x, *y))
+                .unzip();
+            match correlation {
+                Correlation::Pearson => pearson(&x, &y),
+                Correlation::Spearman => pearson(&ranks(&x), &ranks(&y))
+            }
+        }
+        (SensorValues::Categorical(a), SensorValues::Categorical(b)) => {
+            let pairs: Vec<(Rc<str>, Rc<str>)> = value_pairs(a, b).into_iter()
+                .map(|(x, y)| (x.clone(), y.clone()))
+                .collect();
+            cramers_v(&pairs)
+        }
+        (SensorValues::Categorical(categories), SensorValues::Numerical(values))
+        | (SensorValues::Numerical(values), SensorValues::Categorical(categories)) => {
+            let pairs: Vec<(Rc<str>, f64)> = value_pairs(categories, values).into_iter()
+                .map(|(category, value)| (category.clone(), *value))
+                .collect();
+            correlation_ratio(&pairs)
+        }
+    }
+}
+
+fn value_pairs<'a, A, B>(
+    a: &'a HashMap<NeuronID, Vec<A>>, b: &'a HashMap<NeuronID, Vec<B>>
+) -> Vec<(&'a A, &'a B)> {
+    let mut pairs = Vec::new();
+    for (id, a_values) in a {
+        let b_values = match b.get(id) {
+            Some(b_values) => b_values,
+            None => continue
+        };
+        for a_value in a_values {
+            for b_value in b_values { pairs.push((a_value, b_value)); }
+        }
+    }
+    pairs
+}
+
+fn pearson(x: &[f64], y: &[f64]) -> Option<f64> {
+    let n = x.len() as f64;
+    if x.len() < 2 || x.len() != y.len() { return None }
+
+    let (mean_x, mean_y) = (x.iter().sum::<f64>() / n, y.iter().sum::<f64>() / n);
+    let mut covariance = 0.0;
+    let (mut variance_x, mut variance_y) = (0.0, 0.0);
+    for (x, y) in x.iter().zip(y) {
+        covariance += (x - mean_x) * (y - mean_y);
+        variance_x += (x - mean_x).powi(2);
+        variance_y += (y - mean_y).powi(2);
+    }
+    if variance_x == 0.0 || variance_y == 0.0 { return None }
+    Some(covariance / (variance_x * variance_y).sqrt())
+}
+
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by_key(|i| OrderedFloat(values[*i]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start;
+        while end + 1 < order.len() && values[order[end + 1]] == values[order[start]] { end += 1; }
+        let rank = (start + end) as f64 / 2.0 + 1.0;
+        for i in &order[start..=end] { ranks[*i] = rank; }
+        start = end + 1;
+    }
+    ranks
+}
+
+fn cramers_v(pairs: &[(Rc<str>, Rc<str>)]) -> Option<f64> {
+    let n = pairs.len() as f64;
+    let mut rows: HashMap<&Rc<str>, f64> = HashMap::new();
+    let mut cols: HashMap<&Rc<str>, f64> = HashMap::new();
+    let mut cells: HashMap<(&Rc<str>, &Rc<str>), f64> = HashMap::new();
+    for (a, b) in pairs {
+        *rows.entry(a).or_insert(0.0) += 1.0;
+        *cols.entry(b).or_insert(0.0) += 1.0;
+        *cells.entry((a, b)).or_insert(0.0) += 1.0;
+    }
+    let dof = rows.len().min(cols.len()) as f64 - 1.0;
+    if n == 0.0 || dof <= 0.0 { return None }
+
+    let mut chi_squared = 0.0;
+    for (a, row) in &rows {
+        for (b, col) in &cols {
+            let expected = row * col / n;
+            let observed = cells.get(&(*a, *b)).copied().unwrap_or(0.0);
+            chi_squared += (observed - expected).powi(2) / expected;
+        }
+    }
+    Some((chi_squared / (n * dof)).sqrt())
+}
+
+fn correlation_ratio(pairs: &[(Rc<str>, f64)]) -> Option<f64> {
+    if pairs.is_empty() { return None }
+    let mean = pairs.iter().map(|(_, y)| y).sum::<f64>() / pairs.len() as f64;
+
+    let mut groups: HashMap<&Rc<str>, (f64, f64)> = HashMap::new();
+    for (category, y) in pairs {
+        let group = groups.entry(category).or_insert((0.0, 0.0));
+        group.0 += y;
+        group.1 += 1.0;
+    }
+    let between: f64 = groups.values()
+        .map(|(sum, count)| count * (sum / count - mean).powi(2))
+        .sum();
+    let total: f64 = pairs.iter().map(|(_, y)| (y - mean).powi(2)).sum();
+    if total == 0.0 { return None }
+    Some((between / total).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        rc::Rc
+    };
+
+    use ordered_float::OrderedFloat;
+    use test_log::test;
+
+    use bionet_common::neuron::NeuronID;
+
+    use crate::simple::parser;
+
+    use super::Correlation;
+
+    #[test]
+    fn association_matrix() {
+        let magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+
+        for correlation in [Correlation::Pearson, Correlation::Spearman] {
+            let df = magds.association_matrix_with(correlation).unwrap();
+            println!("{df}");
+            assert_eq!(df.shape(), (5, 6));
+
+            let sensors: Vec<&str> = df.column("sensor").unwrap().utf8().unwrap()
+                .into_no_null_iter()
+                .collect();
+            let value = |row: &str, column: &str| {
+                let i = sensors.iter().position(|s| *s == row).unwrap();
+                df.column(column).unwrap().f64().unwrap().get(i).unwrap()
+            };
+
+            for sensor in &sensors { assert_eq!(value(sensor, sensor), 1.0); }
+            for a in &sensors {
+                for b in &sensors {
+                    assert!((value(a, b) - value(b, a)).abs() < 1e-9);
+                }
+            }
+            assert!(value("petal.length", "petal.width") > 0.9);
+            assert!(value("variety", "petal.length") > 0.9);
+            assert!(value("variety", "sepal.width") < value("variety", "petal.length"));
+        }
+    }
+
+    #[test]
+    fn cramers_v() {
+        let pairs: Vec<(Rc<str>, Rc<str>)> = vec![
+            ("a".into(), "x".into()), ("a".into(), "x".into()),
+            ("b".into(), "y".into()), ("b".into(), "y".into())
+        ];
+        assert!((super::cramers_v(&pairs).unwrap() - 1.0).abs() < 1e-9);
+
+        let pairs: Vec<(Rc<str>, Rc<str>)> = vec![
+            ("a".into(), "x".into()), ("a".into(), "y".into()),
+            ("b".into(), "x".into()), ("b".into(), "y".into())
+        ];
+        assert_eq!(super::cramers_v(&pairs).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn value_pairs() {
+        let a: HashMap<NeuronID, Vec<Rc<str>>> = HashMap::from([
+            (NeuronID::new("1", "test"), vec!["a".into(), "b".into()]),
+            (NeuronID::new("2", "test"), vec!["c".into()])
+        ]);
+        let b: HashMap<NeuronID, Vec<f64>> = HashMap::from([
+            (NeuronID::new("1", "test"), vec![1.0, 2.0]),
+            (NeuronID::new("3", "test"), vec![3.0])
+        ]);
+
+        let mut pairs: Vec<(Rc<str>, f64)> = super::value_pairs(&a, &b).into_iter()
+            .map(|(a, b)| (a.clone(), *b))
+            .collect();
+        pairs.sort_by(|x, y| (&x.0, OrderedFloat(x.1)).cmp(&(&y.0, OrderedFloat(y.1))));
+        assert_eq!(pairs, vec![
+            ("a".into(), 1.0), ("a".into(), 2.0), ("b".into(), 1.0), ("b".into(), 2.0)
+        ]);
+    }
+}
@@ -0,0 +1,179 @@
+use std::{
+    collections::{ HashMap, HashSet },
+    rc::Rc
+};
+
+use ordered_float::OrderedFloat;
+
+use bionet_common::neuron::NeuronID;
+
+use crate::simple::magds::MAGDS;
+
+#[derive(Debug, Clone)]
+pub struct ClusterOptions {
+    pub parent_id: Rc<str>,
+    pub threshold: f32,
+    pub iterations: usize,
+    pub fuzzy: bool,
+    pub characteristic_elements: usize
+}
+
+impl ClusterOptions {
+    pub fn new(parent_id: Rc<str>) -> ClusterOptions {
+        ClusterOptions {
+            parent_id, threshold: 0.75, iterations: 3, fuzzy: true, characteristic_elements: 5
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharacteristicElement {
+    pub element: NeuronID,
+    pub support: f64,
+    pub lift: f64
+}
+
+#[derive(Debug, Clone)]
+pub struct Cluster {
+    pub id: usize,
+    pub members: Vec<NeuronID>,
+    pub characteristics: Vec<CharacteristicElement>
+}
+
+#[derive(Debug, Clone)]
+pub struct Clustering {
+    pub assignments: HashMap<NeuronID, usize>,
+    pub clusters: Vec<Cluster>
+}
+
+pub fn cluster(magds: &mut MAGDS, options: &ClusterOptions) -> Clustering {
+    let mut unassigned: Vec<NeuronID> = magds.neurons.keys()
+        .filter(|id| id.parent_id == options.parent_id)
+        .cloned()
+        .collect();
+    unassigned.sort_by(|a, b| a.id.cmp(&b.id));
+    let neurons_count = unassigned.len() as f64;
+
+    let mut assignments: HashMap<NeuronID, usize> = HashMap::new();
+    let mut clusters: Vec<Cluster> = Vec::new();
+
+    while let Some(seed) = unassigned.first().cloned() {
+        let mut members: HashSet<NeuronID> = HashSet::from([seed.clone()]);
+
+        for _ in 0..options.iterations {
+            let coactivated = coactivate(magds, &members, &assignments, options);
+            let members_count = members.len();
+            members.extend(coactivated);
+            if members.len() == members_count { break }
+        }
+
+        let cluster_id = clusters.len();
+        let mut members: Vec<NeuronID> = members.into_iter().collect();
+        members.sort_by(|a, b| a.id.cmp(&b.id));
+        for member in &members { assignments.insert(member.clone(), cluster_id); }
+        unassigned.retain(|id| !assignments.contains_key(id));
+
+        let characteristics = characteristic_elements(
+            magds, &members, neurons_count, options.characteristic_elements
+        );
+        clusters.push(Cluster { id: cluster_id, members, characteristics });
+    }
+
+    Clustering { assignments, clusters }
+}
+
+fn coactivate(
+    magds: &mut MAGDS,
+    members: &HashSet<NeuronID>,
+    assignments: &HashMap<NeuronID, usize>,
+    options: &ClusterOptions
+) -> Vec<NeuronID> {
+    let signal = 1.0_f32 / members.len() as f32;
+    let mut activated: HashMap<NeuronID, f32> = HashMap::new();
+    for member in members {
+        let neurons = magds.activate_neuron_elements(member, signal, options.fuzzy)
+            .unwrap_or_default();
+        for (id, neuron) in neurons {
+            if id.parent_id != options.parent_id || assignments.contains_key(&id) { continue }
+            activated.insert(id, neuron.borrow().activation());
+        }
+    }
+    magds.deactivate();
+
+    let max_activation = activated.values().cloned().fold(0.0_f32, f32::max);
+    if max_activation == 0.0 { return Vec::new() }
+
+    activated.into_iter()
+        .filter(|(_id, activation)| activation / max_activation >= options.threshold)
+        .map(|(id, _activation)| id)
+        .collect()
+}
+
+fn characteristic_elements(
+    magds: &MAGDS, members: &[NeuronID], neurons_count: f64, limit: usize
+) -> Vec<CharacteristicElement> {
+    let mut counts: HashMap<NeuronID, (usize, usize)> = HashMap::new();
+    for member in members {
+        let elements = match magds.neurons.get(member) {
+            Some(neuron) => neuron.borrow().explain(),
+            None => continue
+        };
+        for (element_id, element) in elements {
+            let counter = element.borrow().counter();
+            counts.entry(element_id).or_insert((0, counter)).0 += 1;
+        }
+    }
+
+    let mut characteristics: Vec<CharacteristicElement> = counts.into_iter()
+        .map(|(element, (count, counter))| {
+            let support = count as f64 / members.len() as f64;
+            let frequency = counter as f64 / neurons_count;
+            let lift = if frequency == 0.0 { 0.0 } else { support / frequency };
+            CharacteristicElement { element, support, lift }
+        })
+        .collect();
+    characteristics.sort_by(|a, b| {
+        (OrderedFloat(b.support), OrderedFloat(b.lift))
+            .cmp(&(OrderedFloat(a.support), OrderedFloat(a.lift)))
+    });
+    characteristics.truncate(limit);
+    characteristics
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use crate::simple::parser;
+
+    use super::ClusterOptions;
+
+    #[test]
+    fn cluster() {
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let neurons_count = magds.neurons.len();
+
+        let options = ClusterOptions::new("iris".into());
+        let clustering = super::cluster(&mut magds, &options);
+        println!("clusters: {}", clustering.clusters.len());
+
+        assert_eq!(clustering.assignments.len(), neurons_count);
+        assert!(clustering.clusters.len() > 1);
+        assert!(clustering.clusters.len() < neurons_count);
+
+        let mut members_count = 0;
+        for cluster in &clustering.clusters {
+            assert!(!cluster.members.is_empty());
+            assert!(cluster.characteristics.len() <= options.characteristic_elements);
+            for member in &cluster.members {
+                assert_eq!(clustering.assignments[member], cluster.id);
+            }
+            members_count += cluster.members.len();
+        }
+        assert_eq!(members_count, neurons_count);
+
+        let largest = clustering.clusters.iter().max_by_key(|c| c.members.len()).unwrap();
+        assert!(largest.characteristics[0].support > 0.5);
+        assert!(largest.characteristics[0].lift >= 1.0);
+    }
+}
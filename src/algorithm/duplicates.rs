@@ -0,0 +1,204 @@
+use std::{
+    collections::{ BTreeMap, HashMap, HashSet },
+    ops::Bound,
+    rc::Rc
+};
+
+use bionet_common::{
+    neuron::NeuronID,
+    data::{ DataTypeValue, DataTypeValueStr }
+};
+
+use crate::simple::{
+    fuzzy,
+    magds::MAGDS,
+    parser::RowValue,
+    sensor::SensorElement
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateGroup {
+    pub neurons: Vec<NeuronID>,
+    pub exact: bool
+}
+
+type Values = Vec<(RowValue, DataTypeValue)>;
+
+struct Record {
+    id: NeuronID,
+    values: BTreeMap<Rc<str>, Values>
+}
+
+pub fn find_duplicates(magds: &MAGDS, tolerance: &HashMap<Rc<str>, f64>) -> Vec<DuplicateGroup> {
+    let mut records: Vec<Record> = magds.neurons.iter()
+        .map(|(id, neuron)| {
+            let mut values: BTreeMap<Rc<str>, Values> = BTreeMap::new();
+            for (element_id, element) in neuron.borrow().explain() {
+                let data_type = element.borrow().data_type();
+                let value = match DataTypeValueStr(&element_id.id).data_type_value(data_type) {
+                    Some(value) => value,
+                    None => DataTypeValue::RcStr(element_id.id.clone())
+                };
+                let key = RowValue::new(&value)
+                    .unwrap_or_else(|| RowValue::String(element_id.id.clone()));
+                values.entry(element_id.parent_id).or_default().push((key, value));
+            }
+            for values in values.values_mut() { values.sort_by(|a, b| a.0.cmp(&b.0)); }
+            Record { id: id.clone(), values }
+        })
+        .collect();
+    records.sort_by(|a, b| (&a.id.parent_id, &a.id.id).cmp(&(&b.id.parent_id, &b.id.id)));
+    let positions: HashMap<NeuronID, usize> = records.iter()
+        .enumerate()
+        .map(|(i, record)| (record.id.clone(), i))
+        .collect();
+
+    let mut grouped = vec![false; records.len()];
+    let mut groups = Vec::new();
+    for anchor in 0..records.len() {
+        if grouped[anchor] { continue }
+
+        let mut candidates: Vec<usize> = match candidates(magds, &records[anchor], tolerance) {
+            Some(ids) => ids.iter().filter_map(|id| positions.get(id).copied()).collect(),
+            None => (0..records.len()).collect()
+        };
+        candidates.sort_unstable();
+
+        let mut members = vec![anchor];
+        for candidate in candidates {
+            if candidate == anchor || grouped[candidate] { continue }
+            if records[candidate].id.parent_id != records[anchor].id.parent_id { continue }
+            if is_duplicate(&records[anchor], &records[candidate], tolerance) {
+                members.push(candidate);
+            }
+        }
+        if members.len() < 2 { continue }
+
+        for member in &members { grouped[*member] = true; }
+        let exact = members.iter()
+            .all(|i| is_duplicate(&records[*i], &records[anchor], &HashMap::new()));
+        let neurons = members.into_iter().map(|i| records[i].id.clone()).collect();
+        groups.push(DuplicateGroup { neurons, exact });
+    }
+    groups
+}
+
+fn candidates(
+    magds: &MAGDS, record: &Record, tolerance: &HashMap<Rc<str>, f64>
+) -> Option<HashSet<NeuronID>> {
+    let (sensor_id, values) = record.values.iter().next()?;
+    let (_key, value) = values.first()?;
+
+    let tolerance = tolerance.get(sensor_id);
+    let elements: Vec<SensorElement> = match (tolerance, fuzzy::numeric_value(value)) {
+        (Some(tolerance), Some(value)) => {
+            let (lower, upper) = (value - tolerance, value + tolerance);
+            magds.numeric_range(sensor_id, Bound::Included(lower), Bound::Included(upper))?
+                .into_iter()
+                .map(|(_value, element)| element)
+                .collect()
+        }
+        _ => {
+            let sensor = magds.sensor(sensor_id.clone())?.borrow();
+            sensor.range(Bound::Included(value), Bound::Included(value)).ok()?
+        }
+    };
+    Some(elements.into_iter().flat_map(|(_element, neurons)| neurons.into_keys()).collect())
+}
+
+fn is_duplicate(a: &Record, b: &Record, tolerance: &HashMap<Rc<str>, f64>) -> bool {
+    if a.values.len() != b.values.len() { return false }
+    a.values.iter().all(|(sensor, values_a)| {
+        let values_b = match b.values.get(sensor) {
+            Some(values) => values,
+            None => return false
+        };
+        if values_a.len() != values_b.len() { return false }
+
+        let tolerance = tolerance.get(sensor);
+        values_a.iter().zip(values_b).all(|((key_x, x), (key_y, y))| {
+            if key_x == key_y { return true }
+            match (tolerance, fuzzy::numeric_value(x), fuzzy::numeric_value(y)) {
+                (Some(tolerance), Some(x), Some(y)) => (x - y).abs() <= *tolerance,
+                _ => false
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use test_log::test;
+
+    use bionet_common::{ neuron::NeuronID, data::DataType };
+
+    use crate::simple::{
+        magds::MAGDS,
+        parser::{ self, ParserOptions }
+    };
+
+    #[test]
+    fn find_duplicates() {
+        let magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+
+        let duplicates = super::find_duplicates(&magds, &HashMap::new());
+        assert_eq!(duplicates.len(), 1);
+        assert!(duplicates[0].exact);
+        assert_eq!(duplicates[0].neurons.len(), 2);
+
+        let tolerance = HashMap::from([
+            ("sepal.length".into(), 0.1), ("sepal.width".into(), 0.1),
+            ("petal.length".into(), 0.1), ("petal.width".into(), 0.1)
+        ]);
+        let near_duplicates = super::find_duplicates(&magds, &tolerance);
+        assert!(near_duplicates.len() > duplicates.len());
+        assert!(near_duplicates.iter().any(|group| !group.exact));
+        assert!(near_duplicates.iter().any(|group| {
+            duplicates[0].neurons.iter().all(|id| group.neurons.contains(id))
+        }));
+    }
+
+    #[test]
+    fn collapse_duplicates() {
+        let options = ParserOptions { collapse_duplicates: true };
+        let magds = parser::magds_from_csv_with("iris", "data/iris.csv", &options).unwrap();
+        assert_eq!(magds.neurons.len(), 149);
+
+        let counters: Vec<usize> = magds.neurons.values().map(|n| n.borrow().counter()).collect();
+        assert_eq!(counters.iter().sum::<usize>(), 150);
+        assert_eq!(counters.iter().filter(|counter| **counter == 2).count(), 1);
+
+        let variety = magds.sensor_search("variety".into(), &"Virginica".to_string().into());
+        assert_eq!(variety.unwrap().borrow().counter(), 49);
+        let sl58 = magds.sensor_search("sepal.length".into(), &5.8_f64.into()).unwrap();
+        assert_eq!(sl58.borrow().counter(), 6);
+
+        assert!(super::find_duplicates(&magds, &HashMap::new()).is_empty());
+        assert!(magds.neuron_from_id(&NeuronID::new("1", "iris")).is_some());
+    }
+
+    #[test]
+    fn anchored_groups() {
+        let mut magds = MAGDS::new();
+        magds.create_sensor("x".into(), DataType::F64).unwrap();
+        for (id, value) in [("1", 5.0_f64), ("2", 5.25), ("3", 5.5), ("4", 5.5)] {
+            let neuron = magds.create_neuron(NeuronID::new(id, "points")).unwrap();
+            magds.sensor_insert_connected("x".into(), &value.into(), &neuron).unwrap();
+        }
+
+        let tolerance = HashMap::from([("x".into(), 0.25)]);
+        let duplicates = super::find_duplicates(&magds, &tolerance);
+        let ids = |ids: &[&str]| -> Vec<NeuronID> {
+            ids.iter().map(|id| NeuronID::new(id, "points")).collect()
+        };
+        // 5.25 is within tolerance of both 5.0 and 5.5, but 5.0 and 5.5 are not within
+        // tolerance of each other, so they must not end up in one group
+        assert_eq!(duplicates.len(), 2);
+        assert_eq!(duplicates[0].neurons, ids(&["1", "2"]));
+        assert!(!duplicates[0].exact);
+        assert_eq!(duplicates[1].neurons, ids(&["3", "4"]));
+        assert!(duplicates[1].exact);
+    }
+}
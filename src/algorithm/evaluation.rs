@@ -0,0 +1,329 @@
+use std::{
+    collections::{ HashMap, BTreeSet },
+    rc::Rc
+};
+
+use enum_as_inner::EnumAsInner;
+
+use bionet_common::data::DataCategory;
+
+use crate::{
+    simple::magds::MAGDS,
+    algorithm::predict
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassMetrics {
+    pub class: Rc<str>,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+    pub support: usize
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AveragedMetrics {
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassificationReport {
+    pub accuracy: f64,
+    pub classes: Vec<Rc<str>>,
+    pub confusion_matrix: Vec<Vec<usize>>,
+    pub per_class: Vec<ClassMetrics>,
+    pub macro_avg: AveragedMetrics,
+    pub weighted_avg: AveragedMetrics,
+    pub mean_confidence: f64,
+    pub evaluated: usize,
+    pub skipped: usize
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegressionReport {
+    pub mae: f64,
+    pub rmse: f64,
+    pub r2: f64,
+    pub mean_confidence: f64,
+    pub evaluated: usize,
+    pub skipped: usize
+}
+
+#[derive(Debug, Clone, PartialEq, EnumAsInner)]
+pub enum EvaluationReport {
+    Classification(ClassificationReport),
+    Regression(RegressionReport)
+}
+
+pub fn evaluate(
+    train: &mut MAGDS, test: &MAGDS, target: Rc<str>, fuzzy: bool
+) -> Option<EvaluationReport> {
+    let target_data_category = train.sensor_data_category(target.clone())?;
+
+    let mut labels: Vec<(Rc<str>, Rc<str>)> = Vec::new();
+    let mut confidences: Vec<f64> = Vec::new();
+    let mut skipped = 0usize;
+
+    for (i, (neuron_id, neuron)) in test.neurons.iter().enumerate() {
+        if (i + 1) % 100 == 0 { log::debug!("evaluation iteration: {}", i + 1); }
+
+        let records = neuron.borrow().counter();
+        let (features, targets) = predict::neuron_features(&neuron.borrow(), &target);
+        let reference = match targets.into_iter().next() {
+            Some(reference) => reference,
+            None => {
+                log::warn!("target feature {target} is None for {neuron_id}, skipping");
+                skipped += records;
+                continue
+            }
+        };
+
+        let features = predict::weighted_features(&features, train.feature_weights());
+        let winner = predict::predict_winner(train, &features, fuzzy);
+        let predicted = winner.and_then(|(winner, proba)| {
+            let predicted = predict::winner_target(&winner.borrow().explain(), &target)?;
+            Some((predicted, proba))
+        });
+        train.deactivate();
+
+        match predicted {
+            Some((predicted, proba)) => {
+                log::debug!("predicted {predicted}, reference {reference}");
+                for _ in 0..records {
+                    labels.push((reference.id.clone(), predicted.id.clone()));
+                    confidences.push(proba);
+                }
+            }
+            None => {
+                log::warn!("cannot predict {target} for {neuron_id}, skipping");
+                skipped += records;
+            }
+        }
+    }
+
+    let report = match target_data_category {
+        DataCategory::Numerical => {
+            let mut values: Vec<(f64, f64)> = Vec::new();
+            let mut value_confidences: Vec<f64> = Vec::new();
+            for ((reference, predicted), proba) in labels.iter().zip(&confidences) {
+                match (reference.parse::<f64>(), predicted.parse::<f64>()) {
+                    (Ok(reference), Ok(predicted)) => {
+                        values.push((reference, predicted));
+                        value_confidences.push(*proba);
+                    }
+                    _ => skipped += 1
+                }
+            }
+            EvaluationReport::Regression(
+                regression_report(&values, &value_confidences, skipped)
+            )
+        }
+        DataCategory::Categorical | DataCategory::Ordinal => {
+            EvaluationReport::Classification(
+                classification_report(&labels, &confidences, skipped)
+            )
+        }
+    };
+
+    Some(report)
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() { return 0.0 }
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+pub(crate) fn classification_report(
+    labels: &[(Rc<str>, Rc<str>)], confidences: &[f64], skipped: usize
+) -> ClassificationReport {
+    let classes: Vec<Rc<str>> = labels.iter()
+        .flat_map(|(reference, predicted)| [reference.clone(), predicted.clone()])
+        .collect::<BTreeSet<Rc<str>>>()
+        .into_iter()
+        .collect();
+    let class_index: HashMap<Rc<str>, usize> = classes.iter()
+        .enumerate()
+        .map(|(i, class)| (class.clone(), i))
+        .collect();
+
+    let mut confusion_matrix = vec![vec![0usize; classes.len()]; classes.len()];
+    for (reference, predicted) in labels {
+        confusion_matrix[class_index[reference]][class_index[predicted]] += 1;
+    }
+
+    let total = labels.len();
+    let correct: usize = (0..classes.len()).map(|i| confusion_matrix[i][i]).sum();
+    let accuracy = if total == 0 { 0.0 } else { correct as f64 / total as f64 };
+
+    let mut per_class: Vec<ClassMetrics> = Vec::new();
+    for (i, class) in classes.iter().enumerate() {
+        let true_positives = confusion_matrix[i][i] as f64;
+        let support: usize = confusion_matrix[i].iter().sum();
+        let predicted: usize = confusion_matrix.iter().map(|row| row[i]).sum();
+
+        let precision = if predicted == 0 { 0.0 } else { true_positives / predicted as f64 };
+        let recall = if support == 0 { 0.0 } else { true_positives / support as f64 };
+        let f1 = if precision + recall == 0.0 {
+            0.0
+        } else {
+            2.0 * precision * recall / (precision + recall)
+        };
+
+        per_class.push(ClassMetrics { class: class.clone(), precision, recall, f1, support });
+    }
+
+    let macro_avg = AveragedMetrics {
+        precision: mean(&per_class.iter().map(|m| m.precision).collect::<Vec<_>>()),
+        recall: mean(&per_class.iter().map(|m| m.recall).collect::<Vec<_>>()),
+        f1: mean(&per_class.iter().map(|m| m.f1).collect::<Vec<_>>())
+    };
+
+    let weighted = |metric: fn(&ClassMetrics) -> f64| {
+        if total == 0 { return 0.0 }
+        per_class.iter().map(|m| metric(m) * m.support as f64).sum::<f64>() / total as f64
+    };
+    let weighted_avg = AveragedMetrics {
+        precision: weighted(|m| m.precision),
+        recall: weighted(|m| m.recall),
+        f1: weighted(|m| m.f1)
+    };
+
+    ClassificationReport {
+        accuracy,
+        classes,
+        confusion_matrix,
+        per_class,
+        macro_avg,
+        weighted_avg,
+        mean_confidence: mean(confidences),
+        evaluated: total,
+        skipped
+    }
+}
+
+pub(crate) fn regression_report(
+    values: &[(f64, f64)], confidences: &[f64], skipped: usize
+) -> RegressionReport {
+    let total = values.len();
+    if total == 0 {
+        return RegressionReport {
+            mae: 0.0, rmse: 0.0, r2: 0.0, mean_confidence: 0.0, evaluated: 0, skipped
+        }
+    }
+
+    let reference_mean = values.iter().map(|(reference, _)| reference).sum::<f64>() / total as f64;
+
+    let mut absolute_error = 0.0;
+    let mut squared_error = 0.0;
+    let mut total_variance = 0.0;
+    for (reference, predicted) in values {
+        absolute_error += (reference - predicted).abs();
+        squared_error += (reference - predicted).powi(2);
+        total_variance += (reference - reference_mean).powi(2);
+    }
+
+    let r2 = if total_variance == 0.0 { 0.0 } else { 1.0 - squared_error / total_variance };
+
+    RegressionReport {
+        mae: absolute_error / total as f64,
+        rmse: (squared_error / total as f64).sqrt(),
+        r2,
+        mean_confidence: mean(confidences),
+        evaluated: total,
+        skipped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use test_log::test;
+
+    use crate::simple::parser::{ self, ParserOptions };
+
+    #[test]
+    fn classification_report() {
+        let labels: Vec<(Rc<str>, Rc<str>)> = vec![
+            ("a".into(), "a".into()),
+            ("a".into(), "a".into()),
+            ("a".into(), "b".into()),
+            ("b".into(), "b".into()),
+            ("b".into(), "a".into()),
+            ("c".into(), "c".into())
+        ];
+        let report = super::classification_report(&labels, &[1.0, 0.5, 0.5, 1.0, 0.5, 0.5], 2);
+
+        assert_eq!(report.classes, vec![Rc::from("a"), Rc::from("b"), Rc::from("c")]);
+        assert_eq!(report.confusion_matrix, vec![vec![2, 1, 0], vec![1, 1, 0], vec![0, 0, 1]]);
+        assert_eq!(report.evaluated, 6);
+        assert_eq!(report.skipped, 2);
+        assert!((report.accuracy - 4.0 / 6.0).abs() < 1e-9);
+        assert!((report.mean_confidence - 4.0 / 6.0).abs() < 1e-9);
+
+        let a = &report.per_class[0];
+        assert!((a.precision - 2.0 / 3.0).abs() < 1e-9);
+        assert!((a.recall - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(a.support, 3);
+        let c = &report.per_class[2];
+        assert_eq!(c.f1, 1.0);
+
+        let macro_recall = (2.0 / 3.0 + 0.5 + 1.0) / 3.0;
+        assert!((report.macro_avg.recall - macro_recall).abs() < 1e-9);
+        let weighted_recall = (2.0 / 3.0 * 3.0 + 0.5 * 2.0 + 1.0) / 6.0;
+        assert!((report.weighted_avg.recall - weighted_recall).abs() < 1e-9);
+    }
+
+    #[test]
+    fn regression_report() {
+        let values = vec![(1.0, 2.0), (2.0, 2.0), (3.0, 2.0)];
+        let report = super::regression_report(&values, &[1.0, 1.0, 1.0], 0);
+        assert!((report.mae - 2.0 / 3.0).abs() < 1e-9);
+        assert!((report.rmse - (2.0_f64 / 3.0).sqrt()).abs() < 1e-9);
+        assert!(report.r2.abs() < 1e-9);
+        assert_eq!(report.evaluated, 3);
+    }
+
+    #[test]
+    fn evaluate_iris() {
+        let mut magds_train = parser::magds_from_csv("iris_train", "data/iris_train.csv").unwrap();
+        let magds_test = parser::magds_from_csv("iris_test", "data/iris_test.csv").unwrap();
+
+        let report = super::evaluate(&mut magds_train, &magds_test, "variety".into(), false)
+            .unwrap()
+            .into_classification()
+            .unwrap();
+        println!("{:?}", report);
+        assert!(report.accuracy > 0.95);
+        assert_eq!(report.classes.len(), 3);
+        let matrix_total: usize = report.confusion_matrix.iter().flatten().sum();
+        assert_eq!(matrix_total, report.evaluated);
+        assert_eq!(report.evaluated + report.skipped, magds_test.neurons.len());
+
+        let report = super::evaluate(&mut magds_train, &magds_test, "petal.length".into(), true)
+            .unwrap()
+            .into_regression()
+            .unwrap();
+        println!("{:?}", report);
+        assert!(report.evaluated > 0);
+        assert!(report.mae <= report.rmse);
+    }
+
+    #[test]
+    fn evaluate_collapsed() {
+        let mut magds_train = parser::magds_from_csv("iris_train", "data/iris_train.csv").unwrap();
+        let options = ParserOptions { collapse_duplicates: true };
+        let magds_test = parser::magds_from_csv_with("iris", "data/iris.csv", &options).unwrap();
+        assert_eq!(magds_test.neurons.len(), 149);
+
+        let report = super::evaluate(&mut magds_train, &magds_test, "variety".into(), false)
+            .unwrap()
+            .into_classification()
+            .unwrap();
+        assert_eq!(report.evaluated + report.skipped, 150);
+        let support: usize = report.per_class.iter().map(|class| class.support).sum();
+        assert_eq!(support, report.evaluated);
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    collections::HashMap,
+    rc::Rc
+};
+
+use bionet_common::neuron::NeuronID;
+
+use crate::simple::magds::MAGDS;
+
+#[derive(Default)]
+struct SensorCounts {
+    total: f64,
+    elements: HashMap<NeuronID, f64>,
+    classes: HashMap<NeuronID, f64>,
+    joint: HashMap<(NeuronID, NeuronID), f64>
+}
+
+pub fn learn_feature_weights(magds: &MAGDS, target: Rc<str>) -> HashMap<Rc<str>, f32> {
+    let mut counts: HashMap<Rc<str>, SensorCounts> = HashMap::new();
+
+    for neuron in magds.neurons.values() {
+        let (targets, elements): (Vec<NeuronID>, Vec<NeuronID>) = neuron.borrow()
+            .defining_sensors()
+            .into_keys()
+            .partition(|id| id.parent_id == target);
+        if targets.is_empty() { continue }
+
+        for element in &elements {
+            let sensor_counts = counts.entry(element.parent_id.clone()).or_default();
+            for class in &targets {
+                sensor_counts.total += 1.0;
+                *sensor_counts.elements.entry(element.clone()).or_insert(0.0) += 1.0;
+                *sensor_counts.classes.entry(class.clone()).or_insert(0.0) += 1.0;
+                *sensor_counts.joint
+                    .entry((element.clone(), class.clone()))
+                    .or_insert(0.0) += 1.0;
+            }
+        }
+    }
+
+    let mut weights: HashMap<Rc<str>, f32> = HashMap::new();
+    for sensor_id in magds.sensors.keys() {
+        if *sensor_id == target { continue }
+        let weight = match counts.get(sensor_id) {
+            Some(sensor_counts) => symmetric_uncertainty(sensor_counts),
+            None => 0.0
+        };
+        weights.insert(sensor_id.clone(), weight as f32);
+    }
+
+    weights
+}
+
+pub fn learn_and_store_feature_weights(
+    magds: &mut MAGDS, target: Rc<str>
+) -> HashMap<Rc<str>, f32> {
+    let weights = learn_feature_weights(magds, target);
+    magds.set_feature_weights(weights.clone());
+    weights
+}
+
+fn entropy<'a>(counts: impl Iterator<Item = &'a f64>, total: f64) -> f64 {
+    counts
+        .map(|count| {
+            let p = count / total;
+            -p * p.ln()
+        })
+        .sum()
+}
+
+fn symmetric_uncertainty(counts: &SensorCounts) -> f64 {
+    if counts.total == 0.0 { return 0.0 }
+
+    let class_entropy = entropy(counts.classes.values(), counts.total);
+    let element_entropy = entropy(counts.elements.values(), counts.total);
+    if class_entropy + element_entropy == 0.0 { return 0.0 }
+
+    let mut mutual_information = 0.0;
+    for ((element, class), joint_count) in &counts.joint {
+        let p_joint = joint_count / counts.total;
+        let p_element = counts.elements[element] / counts.total;
+        let p_class = counts.classes[class] / counts.total;
+        mutual_information += p_joint * (p_joint / (p_element * p_class)).ln();
+    }
+
+    (2.0 * mutual_information / (class_entropy + element_entropy)).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use test_log::test;
+
+    use bionet_common::data::{ DataType, DataTypeValue };
+
+    use crate::{
+        algorithm::predict,
+        simple::parser
+    };
+
+    #[test]
+    fn learn_feature_weights() {
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let weights = super::learn_feature_weights(&magds, "variety".into());
+        println!("{:?}", weights);
+
+        assert_eq!(weights.len(), 4);
+        assert!(!weights.contains_key("variety"));
+        for weight in weights.values() { assert!(*weight >= 0.0 && *weight <= 1.0); }
+        assert!(weights["petal.width"] > weights["sepal.width"]);
+
+        let features: Vec<(Rc<str>, _)> = vec![
+            ("sepal.length".into(), 5.8_f64.into()),
+            ("petal.length".into(), 4.1_f64.into()),
+            ("petal.width".into(), 1.3_f64.into())
+        ];
+        let weighted = predict::weighted_features(&features, &weights);
+        assert_eq!(weighted[2].2, weights["petal.width"]);
+        let prediction = predict::predict_weighted(&mut magds, weighted, "variety".into(), false);
+        assert!(prediction.is_some());
+
+        super::learn_and_store_feature_weights(&mut magds, "variety".into());
+        assert_eq!(magds.feature_weight("petal.width".into()), Some(weights["petal.width"]));
+    }
+
+    #[test]
+    fn high_cardinality() {
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        magds.create_sensor("row".into(), DataType::U32).unwrap();
+        for row in 1..=150_u32 {
+            let neuron = magds.neuron(&row.to_string(), "iris").unwrap();
+            let value: DataTypeValue = row.into();
+            magds.sensor_insert_connected("row".into(), &value, &neuron).unwrap();
+        }
+
+        let weights = super::learn_feature_weights(&magds, "variety".into());
+        assert!(weights["row"] < 0.5);
+        assert!(weights["row"] < weights["petal.width"]);
+        assert!(weights["row"] < weights["petal.length"]);
+    }
+}
@@ -0,0 +1,224 @@
+use std::{
+    collections::HashMap,
+    rc::Rc
+};
+
+use ordered_float::OrderedFloat;
+
+use bionet_common::{
+    data::{ DataType, DataTypeValue, DataTypeValueStr, DataCategory },
+    neuron::NeuronID
+};
+
+use crate::{
+    simple::magds::MAGDS,
+    algorithm::predict
+};
+
+#[derive(Debug, Clone)]
+pub struct ImputeOptions {
+    pub fuzzy: bool,
+    pub neighbours: usize,
+    pub connect: bool
+}
+
+impl Default for ImputeOptions {
+    fn default() -> Self {
+        ImputeOptions { fuzzy: false, neighbours: 5, connect: false }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImputedValue {
+    pub neuron: NeuronID,
+    pub value: DataTypeValue,
+    pub confidence: f64,
+    pub connected: bool
+}
+
+#[derive(Debug, Clone)]
+pub struct ImputationReport {
+    pub sensor: Rc<str>,
+    pub imputed: Vec<ImputedValue>,
+    pub failed: Vec<NeuronID>
+}
+
+pub fn impute(
+    magds: &mut MAGDS, sensor_id: Rc<str>, options: &ImputeOptions
+) -> Result<ImputationReport, String> {
+    let data_type = magds.sensor_data_type(sensor_id.clone())
+        .ok_or(format!("sensor {sensor_id} doesn't exists"))?;
+    let data_category = magds.sensor_data_category(sensor_id.clone())
+        .ok_or(format!("sensor {sensor_id} doesn't exists"))?;
+
+    let missing: Vec<NeuronID> = magds.neurons.iter()
+        .filter(|(_id, neuron)| {
+            !neuron.borrow().defining_sensors().keys().any(|id| id.parent_id == sensor_id)
+        })
+        .map(|(id, _neuron)| id.clone())
+        .collect();
+
+    let mut report = ImputationReport {
+        sensor: sensor_id.clone(), imputed: Vec::new(), failed: Vec::new()
+    };
+
+    for neuron_id in missing {
+        let neuron = magds.neurons[&neuron_id].clone();
+        let (features, _) = predict::neuron_features(&neuron.borrow(), &sensor_id);
+        let features = predict::weighted_features(&features, magds.feature_weights());
+
+        let activated = predict::activate_features(magds, &features, options.fuzzy);
+        let mut votes: Vec<(f32, NeuronID)> = Vec::new();
+        let mut neighbours = 0usize;
+        for (activation, associated) in predict::rank_neurons(&activated) {
+            if neighbours >= options.neighbours { break }
+            let associated = associated.borrow();
+            if associated.id() == neuron_id { continue }
+            let elements: Vec<NeuronID> = associated.explain().into_keys()
+                .filter(|id| id.parent_id == sensor_id)
+                .collect();
+            if elements.is_empty() { continue }
+            neighbours += 1;
+            votes.extend(elements.into_iter().map(|id| (activation, id)));
+        }
+        magds.deactivate();
+
+        let imputed = match data_category {
+            DataCategory::Numerical => numerical_vote(&votes, &data_type),
+            DataCategory::Categorical | DataCategory::Ordinal => {
+                categorical_vote(&votes, &data_type)
+            }
+        };
+
+        match imputed {
+            Some((value, confidence)) => {
+                log::debug!("imputed {sensor_id} {:?} for {neuron_id}", value);
+                report.imputed.push(ImputedValue {
+                    neuron: neuron_id, value, confidence, connected: false
+                });
+            }
+            None => {
+                log::warn!("cannot impute {sensor_id} for {neuron_id}");
+                report.failed.push(neuron_id);
+            }
+        }
+    }
+
+    if options.connect {
+        for imputed in &mut report.imputed {
+            let neuron = magds.neurons[&imputed.neuron].clone();
+            match magds.sensor_insert_connected(sensor_id.clone(), &imputed.value, &neuron) {
+                Ok(_) => imputed.connected = true,
+                Err(e) => {
+                    log::error!("cannot connect imputed value to {}, error: {e}", imputed.neuron)
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+fn categorical_vote(
+    votes: &[(f32, NeuronID)], data_type: &DataType
+) -> Option<(DataTypeValue, f64)> {
+    let mut scores: HashMap<Rc<str>, f64> = HashMap::new();
+    for (activation, element_id) in votes {
+        *scores.entry(element_id.id.clone()).or_insert(0.0) += *activation as f64;
+    }
+    let total: f64 = scores.values().sum();
+    let (winner, score) = scores.into_iter()
+        .max_by_key(|(value, score)| (OrderedFloat(*score), value.clone()))?;
+
+    let value = DataTypeValueStr(&winner).data_type_value(data_type.clone())?;
+    let confidence = if total == 0.0 { 0.0 } else { score / total };
+    Some((value, confidence))
+}
+
+fn numerical_vote(
+    votes: &[(f32, NeuronID)], data_type: &DataType
+) -> Option<(DataTypeValue, f64)> {
+    let mut weighted_sum = 0.0;
+    let mut total_weight = 0.0;
+    let mut values: Vec<f64> = Vec::new();
+    for (activation, element_id) in votes {
+        let value = match element_id.id.parse::<f64>() {
+            Ok(v) => v,
+            Err(_) => continue
+        };
+        weighted_sum += value * *activation as f64;
+        total_weight += *activation as f64;
+        values.push(value);
+    }
+    if values.is_empty() { return None }
+
+    let mean = if total_weight == 0.0 {
+        values.iter().sum::<f64>() / values.len() as f64
+    } else {
+        weighted_sum / total_weight
+    };
+
+    let spread = values.iter().map(|v| (v - mean).abs()).sum::<f64>() / values.len() as f64;
+    let confidence = if mean == 0.0 { 1.0 / (1.0 + spread) } else {
+        (1.0 - spread / mean.abs()).clamp(0.0, 1.0)
+    };
+
+    Some((value_from_f64(mean, data_type)?, confidence))
+}
+
+pub(crate) fn value_from_f64(value: f64, data_type: &DataType) -> Option<DataTypeValue> {
+    let value_str: Rc<str> = match data_type {
+        DataType::U8 | DataType::U16 | DataType::U32 | DataType::U64 | DataType::U128
+        | DataType::USize | DataType::I8 | DataType::I16 | DataType::I32 | DataType::I64
+        | DataType::I128 | DataType::ISize => Rc::from((value.round() as i128).to_string()),
+        _ => Rc::from(value.to_string())
+    };
+    DataTypeValueStr(&value_str).data_type_value(data_type.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use crate::{
+        simple::parser,
+        neuron::simple_neuron::SimpleNeuron
+    };
+
+    use super::ImputeOptions;
+
+    #[test]
+    fn impute_numerical() {
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let neuron_1 = magds.neuron("1", "iris").unwrap();
+        let sepal_length = |neuron: &SimpleNeuron| {
+            neuron.explain().into_keys().find(|id| &*id.parent_id == "sepal.length")
+        };
+        assert!(sepal_length(&*neuron_1.borrow()).is_none());
+
+        let options = ImputeOptions { connect: true, ..ImputeOptions::default() };
+        let report = super::impute(&mut magds, "sepal.length".into(), &options).unwrap();
+        println!("{:?}", report);
+
+        assert_eq!(report.failed.len(), 0);
+        let imputed = report.imputed.iter()
+            .find(|imputed| imputed.neuron == neuron_1.borrow().id())
+            .unwrap();
+        assert!(imputed.connected);
+        let value = *imputed.value.as_f64().unwrap();
+        assert!(value > 4.3 && value < 5.8);
+
+        let connected = sepal_length(&*neuron_1.borrow()).unwrap();
+        assert_eq!(connected.id.parse::<f64>().unwrap(), value);
+
+        let report = super::impute(&mut magds, "sepal.length".into(), &options).unwrap();
+        assert!(report.imputed.is_empty());
+    }
+
+    #[test]
+    fn impute_unknown_sensor() {
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let report = super::impute(&mut magds, "unknown".into(), &ImputeOptions::default());
+        assert!(report.is_err());
+    }
+}
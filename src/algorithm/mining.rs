@@ -0,0 +1,274 @@
+use std::{
+    collections::{ HashMap, HashSet },
+    rc::Rc
+};
+
+use ordered_float::OrderedFloat;
+
+use bionet_common::neuron::NeuronID;
+
+use crate::simple::magds::MAGDS;
+
+#[derive(Debug, Clone)]
+pub struct MiningOptions {
+    pub parent_id: Rc<str>,
+    pub min_support: f64,
+    pub min_confidence: f64,
+    pub max_length: usize
+}
+
+impl MiningOptions {
+    pub fn new(parent_id: Rc<str>) -> MiningOptions {
+        MiningOptions { parent_id, min_support: 0.1, min_confidence: 0.8, max_length: 3 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Itemset {
+    pub elements: Vec<NeuronID>,
+    pub count: usize,
+    pub support: f64
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssociationRule {
+    pub antecedent: Vec<NeuronID>,
+    pub consequent: Vec<NeuronID>,
+    pub support: f64,
+    pub confidence: f64,
+    pub lift: f64
+}
+
+struct Transactions {
+    items: Vec<NeuronID>,
+    transactions: Vec<(HashSet<usize>, usize)>,
+    records_count: usize
+}
+
+impl Transactions {
+    fn new(magds: &MAGDS, options: &MiningOptions) -> Transactions {
+        let neurons: Vec<_> = magds.neurons.iter()
+            .filter(|(id, _neuron)| id.parent_id == options.parent_id)
+            .map(|(_id, neuron)| neuron.clone())
+            .collect();
+        let records_count = magds.records_count(&options.parent_id);
+        let element_records = magds.element_records(&options.parent_id);
+
+        let mut items: Vec<NeuronID> = Vec::new();
+        let mut item_index: HashMap<NeuronID, usize> = HashMap::new();
+        let mut transactions: Vec<(HashSet<usize>, usize)> = Vec::new();
+        for neuron in neurons {
+            let mut transaction: HashSet<usize> = HashSet::new();
+            for element_id in neuron.borrow().explain().into_keys() {
+                let records = element_records[&element_id] as f64;
+                if records / records_count as f64 < options.min_support { continue }
+                let index = *item_index.entry(element_id.clone()).or_insert_with(|| {
+                    items.push(element_id);
+                    items.len() - 1
+                });
+                transaction.insert(index);
+            }
+            transactions.push((transaction, neuron.borrow().counter()));
+        }
+
+        Transactions { items, transactions, records_count }
+    }
+
+    fn count(&self, itemset: &[usize]) -> usize {
+        self.transactions.iter()
+            .filter(|(transaction, _)| itemset.iter().all(|item| transaction.contains(item)))
+            .map(|(_, records)| records)
+            .sum()
+    }
+
+    fn elements(&self, itemset: &[usize]) -> Vec<NeuronID> {
+        itemset.iter().map(|item| self.items[*item].clone()).collect()
+    }
+}
+
+fn mine(transactions: &Transactions, options: &MiningOptions) -> HashMap<Vec<usize>, usize> {
+    let transactions_count = transactions.records_count as f64;
+    let mut frequent: HashMap<Vec<usize>, usize> = HashMap::new();
+    if transactions_count == 0.0 { return frequent }
+
+    let is_frequent = |count: usize| count as f64 / transactions_count >= options.min_support;
+
+    let mut level: Vec<Vec<usize>> = Vec::new();
+    for item in 0..transactions.items.len() {
+        let count = transactions.count(&[item]);
+        if is_frequent(count) {
+            frequent.insert(vec![item], count);
+            level.push(vec![item]);
+        }
+    }
+
+    let mut length = 1;
+    while !level.is_empty() && length < options.max_length {
+        level.sort();
+        let mut next_level: Vec<Vec<usize>> = Vec::new();
+        for i in 0..level.len() {
+            for j in (i + 1)..level.len() {
+                let (a, b) = (&level[i], &level[j]);
+                if a[..length - 1] != b[..length - 1] { break }
+
+                let last = b[length - 1];
+                let sensor = &transactions.items[last].parent_id;
+                if a.iter().any(|item| transactions.items[*item].parent_id == *sensor) { continue }
+
+                let mut candidate = a.clone();
+                candidate.push(last);
+                candidate.sort();
+
+                let all_subsets_frequent = (0..candidate.len()).all(|skip| {
+                    let subset: Vec<usize> = candidate.iter()
+                        .enumerate()
+                        .filter(|(k, _)| *k != skip)
+                        .map(|(_, item)| *item)
+                        .collect();
+                    frequent.contains_key(&subset)
+                });
+                if !all_subsets_frequent { continue }
+
+                let count = transactions.count(&candidate);
+                if is_frequent(count) {
+                    frequent.insert(candidate.clone(), count);
+                    next_level.push(candidate);
+                }
+            }
+        }
+        level = next_level;
+        length += 1;
+    }
+
+    frequent
+}
+
+pub fn frequent_itemsets(magds: &MAGDS, options: &MiningOptions) -> Vec<Itemset> {
+    let transactions = Transactions::new(magds, options);
+    let transactions_count = transactions.records_count as f64;
+
+    let mut itemsets: Vec<Itemset> = mine(&transactions, options).into_iter()
+        .map(|(itemset, count)| Itemset {
+            elements: transactions.elements(&itemset),
+            count,
+            support: count as f64 / transactions_count
+        })
+        .collect();
+    itemsets.sort_by(|a, b| {
+        (OrderedFloat(b.support), a.elements.len())
+            .cmp(&(OrderedFloat(a.support), b.elements.len()))
+    });
+    itemsets
+}
+
+pub fn association_rules(magds: &MAGDS, options: &MiningOptions) -> Vec<AssociationRule> {
+    let transactions = Transactions::new(magds, options);
+    let transactions_count = transactions.records_count as f64;
+    let frequent = mine(&transactions, options);
+
+    let mut rules: Vec<AssociationRule> = Vec::new();
+    for (itemset, count) in &frequent {
+        if itemset.len() < 2 { continue }
+        let support = *count as f64 / transactions_count;
+
+        for mask in 1..((1usize << itemset.len()) - 1) {
+            let (antecedent, consequent): (Vec<(usize, &usize)>, Vec<(usize, &usize)>) = itemset
+                .iter()
+                .enumerate()
+                .partition(|(k, _)| mask & (1 << *k) != 0);
+            let antecedent: Vec<usize> = antecedent.into_iter().map(|(_, item)| *item).collect();
+            let consequent: Vec<usize> = consequent.into_iter().map(|(_, item)| *item).collect();
+
+            let antecedent_count = frequent[&antecedent] as f64;
+            let consequent_support = frequent[&consequent] as f64 / transactions_count;
+            let confidence = *count as f64 / antecedent_count;
+            if confidence < options.min_confidence { continue }
+
+            rules.push(AssociationRule {
+                antecedent: transactions.elements(&antecedent),
+                consequent: transactions.elements(&consequent),
+                support,
+                confidence,
+                lift: confidence / consequent_support
+            });
+        }
+    }
+
+    rules.sort_by(|a, b| {
+        (OrderedFloat(b.confidence), OrderedFloat(b.lift))
+            .cmp(&(OrderedFloat(a.confidence), OrderedFloat(a.lift)))
+    });
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use crate::simple::parser::{ self, ParserOptions };
+
+    use super::MiningOptions;
+
+    #[test]
+    fn frequent_itemsets() {
+        let magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let options = MiningOptions { min_support: 0.2, ..MiningOptions::new("iris".into()) };
+
+        let itemsets = super::frequent_itemsets(&magds, &options);
+        assert!(!itemsets.is_empty());
+        for itemset in &itemsets {
+            assert!(itemset.support >= options.min_support);
+            assert!(itemset.elements.len() <= options.max_length);
+        }
+
+        let setosa = itemsets.iter()
+            .find(|itemset| {
+                itemset.elements.len() == 1 && &*itemset.elements[0].id == "Setosa"
+            })
+            .unwrap();
+        assert_eq!(setosa.count, 49);
+    }
+
+    #[test]
+    fn association_rules() {
+        let magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let options = MiningOptions { min_support: 0.1, ..MiningOptions::new("iris".into()) };
+
+        let rules = super::association_rules(&magds, &options);
+        assert!(!rules.is_empty());
+        for rule in &rules {
+            assert!(rule.confidence >= options.min_confidence);
+            assert!(rule.support >= options.min_support);
+            assert!(rule.antecedent.iter().all(|a| !rule.consequent.contains(a)));
+        }
+
+        let to_setosa = rules.iter()
+            .find(|rule| rule.consequent.len() == 1 && &*rule.consequent[0].id == "Setosa")
+            .unwrap();
+        assert!(to_setosa.lift > 1.0);
+    }
+
+    #[test]
+    fn collapsed_support() {
+        let options = MiningOptions { min_support: 0.2, ..MiningOptions::new("iris".into()) };
+        let magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let parser_options = ParserOptions { collapse_duplicates: true };
+        let collapsed = parser::magds_from_csv_with(
+            "iris", "data/iris.csv", &parser_options
+        ).unwrap();
+
+        let itemsets = super::frequent_itemsets(&magds, &options);
+        let collapsed_itemsets = super::frequent_itemsets(&collapsed, &options);
+        assert_eq!(itemsets.len(), collapsed_itemsets.len());
+        for itemset in &itemsets {
+            let collapsed_itemset = collapsed_itemsets.iter()
+                .find(|collapsed| {
+                    collapsed.elements.len() == itemset.elements.len()
+                        && itemset.elements.iter().all(|e| collapsed.elements.contains(e))
+                })
+                .unwrap();
+            assert_eq!(collapsed_itemset.count, itemset.count);
+            assert!((collapsed_itemset.support - itemset.support).abs() < 1e-9);
+        }
+    }
+}
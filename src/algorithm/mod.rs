@@ -1 +1,11 @@
-pub mod predict;
\ No newline at end of file
+pub mod predict;
+pub mod evaluation;
+pub mod feature_weights;
+pub mod impute;
+pub mod anomaly;
+pub mod cluster;
+pub mod mining;
+pub mod query;
+pub mod aggregate;
+pub mod association;
+pub mod duplicates;
\ No newline at end of file
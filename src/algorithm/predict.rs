@@ -1,19 +1,64 @@
 use std::{
-    collections::{ HashMap, BTreeMap },
+    cmp::Reverse,
+    collections::{ HashMap, HashSet },
     rc::Rc,
     cell::RefCell
 };
 
 use ordered_float::OrderedFloat;
 
+use polars::prelude::{ DataFrame, Series, NamedFrom };
+
 use bionet_common::{
     data::{ DataTypeValue, DataTypeValueStr, DataCategory },
-    neuron::{ Neuron, NeuronID }, distances::Distance,
-    sensor::Sensor
+    neuron::{ Neuron, NeuronID },
+    polars::{ self as polars_common, DataVecOption }
+};
+
+use crate::{
+    simple::{
+        magds::MAGDS,
+        parser::{ self, RowValue },
+        trace::ActivationDirection
+    },
+    neuron::simple_neuron::SimpleNeuron,
+    algorithm::evaluation::{ self, EvaluationReport }
 };
-use polars::export::num::ToPrimitive;
 
-use crate::simple::magds::MAGDS;
+#[derive(Debug, Clone)]
+pub struct FeatureContribution {
+    pub sensor: Rc<str>,
+    pub element: NeuronID,
+    pub exact: bool,
+    pub activation: f32
+}
+
+#[derive(Debug, Clone)]
+pub struct CandidateExplanation {
+    pub neuron: NeuronID,
+    pub activation: f32,
+    pub value: Option<DataTypeValue>,
+    pub contributions: Vec<FeatureContribution>
+}
+
+#[derive(Debug, Clone)]
+pub struct ExplainedPrediction {
+    pub value: DataTypeValue,
+    pub probability: f64,
+    pub candidates: Vec<CandidateExplanation>
+}
+
+impl ExplainedPrediction {
+    pub fn winner(&self) -> &CandidateExplanation { &self.candidates[0] }
+
+    pub fn runners_up(&self) -> &[CandidateExplanation] { &self.candidates[1..] }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PredictOptions {
+    pub fuzzy: bool,
+    pub features: Option<Vec<Rc<str>>>
+}
 
 pub fn predict(
     magds: &mut MAGDS, 
@@ -21,193 +66,333 @@ pub fn predict(
     target: Rc<str>,
     fuzzy: bool
 ) -> Option<(DataTypeValue, f64)> {
-    let mut neurons: HashMap<NeuronID, Rc<RefCell<dyn Neuron>>> = HashMap::new();
+    let features = weighted_features(features, magds.feature_weights());
+    let (winner, proba) = predict_winner(magds, &features, fuzzy)?;
+    let predicted_value = winner.borrow().explain_one(target)?;
+    Some((predicted_value, proba))
+}
 
-    for (id, value) in features {
-        let sensor = match magds.sensor_search(id.clone(), value) {
-            Some(s) => s,
-            None => {
-                match magds.sensor_data_category(id.clone()) {
-                    Some(DataCategory::Numerical) | Some(DataCategory::Ordinal) => {
-                        if fuzzy {
-                            log::info!("cannot find sensor {id} value {:?}, inserting", value);
-                            match magds.sensor_insert(id.clone(), value) {
-                                Some(s) => s,
-                                None => {
-                                    log::warn!("cannot insert {:?} to {id}, skipping", value);
-                                    continue
-                                }
-                            }
-                        } else {
-                            log::warn!("cannot find sensor {id} for value {:?}, skipping", value);
-                            continue
-                        }
-                    }
-                    _ => {
-                        log::warn!("cannot find sensor {id} for value {:?}, skipping", value);
-                        continue
-                    }
-                }
-            }
-        };
-        neurons.extend(sensor.borrow_mut().activate(1.0_f32, fuzzy, true));
-    }
+pub fn predict_weighted(
+    magds: &mut MAGDS, 
+    features: Vec<(Rc<str>, DataTypeValue, f32)>,
+    target: Rc<str>,
+    fuzzy: bool
+) -> Option<(DataTypeValue, f64)> {
+    let (winner, proba) = predict_winner(magds, &features, fuzzy)?;
+    let predicted_value = winner.borrow().explain_one(target)?;
+    Some((predicted_value, proba))
+}
 
+pub fn predict_explained(
+    magds: &mut MAGDS, 
+    features: &Vec<(Rc<str>, DataTypeValue)>,
+    target: Rc<str>,
+    fuzzy: bool,
+    runners_up: usize
+) -> Option<ExplainedPrediction> {
+    let features = weighted_features(features, magds.feature_weights());
+    let (neurons, element_signals) = activate_features_traced(magds, &features, fuzzy);
     if neurons.is_empty() { return None }
 
-    let neurons_activations: Vec<OrderedFloat<f32>> = neurons.values()
-        .cloned()
-        .map(|neuron| OrderedFloat(neuron.borrow().activation()))
+    let stimulated: HashSet<NeuronID> = features.iter()
+        .filter_map(|(id, value, _)| Some(magds.sensor_search(id.clone(), value)?.borrow().id()))
         .collect();
-    let neurons: Vec<Rc<RefCell<dyn Neuron>>> = neurons.values().cloned().collect();
 
-    let neurons_sorted: BTreeMap<OrderedFloat<f32>, Rc<RefCell<dyn Neuron>>> 
-        = BTreeMap::from_iter(neurons_activations.into_iter().zip(neurons));
+    let mut candidates: Vec<CandidateExplanation> = Vec::new();
+    for (activation, neuron) in rank_neurons(&neurons).into_iter().take(runners_up + 1) {
+        let neuron = neuron.borrow();
+
+        let mut contributions: Vec<FeatureContribution> = Vec::new();
+        for element_id in neuron.explain().into_keys() {
+            if element_id.parent_id == target { continue }
+            let activation = element_signals.get(&(neuron.id(), element_id.clone()))
+                .copied()
+                .unwrap_or(0.0);
+            if activation == 0.0 { continue }
+            contributions.push(FeatureContribution {
+                sensor: element_id.parent_id.clone(),
+                exact: stimulated.contains(&element_id),
+                element: element_id,
+                activation
+            });
+        }
+        contributions.sort_by(|a, b| {
+            OrderedFloat(b.activation).cmp(&OrderedFloat(a.activation))
+        });
+
+        candidates.push(CandidateExplanation {
+            neuron: neuron.id(),
+            activation,
+            value: neuron.explain_one(target.clone()),
+            contributions
+        });
+    }
 
-    let (winner_activation, winner) = neurons_sorted.into_iter().next_back()?;
+    let value = candidates.first()?.value.clone()?;
+    let probability = candidates[0].activation as f64 / features.len() as f64;
 
-    let max_activation = features.len() as f64;
-    let proba = winner_activation.to_f32()? as f64 / max_activation;
+    Some(ExplainedPrediction { value, probability, candidates })
+}
 
-    let predicted_value = winner.borrow().explain_one(target)?;
+pub fn predict_df(
+    magds: &mut MAGDS, df: &DataFrame, target: Rc<str>, options: &PredictOptions
+) -> Result<DataFrame, String> {
+    let target_data_category = magds.sensor_data_category(target.clone())
+        .ok_or(format!("target sensor {target} doesn't exists"))?;
+
+    let feature_names: Vec<Rc<str>> = match &options.features {
+        Some(features) => features.clone(),
+        None => df.get_column_names().into_iter()
+            .map(|name| Rc::from(name))
+            .filter(|name| *name != target && magds.sensor(name.clone()).is_some())
+            .collect()
+    };
 
-    Some((predicted_value, proba))
+    let mut columns: Vec<(Rc<str>, DataVecOption)> = Vec::new();
+    for name in &feature_names {
+        let column = df.column(name).map_err(|e| e.to_string())?;
+        let datavec = polars_common::series_to_datavec(column)
+            .map_err(|e| format!("error convering {name} to datavec, error: {e}"))?;
+        columns.push((name.clone(), datavec));
+    }
+
+    let mut predictions: Vec<Option<Rc<str>>> = Vec::with_capacity(df.height());
+    let mut confidences: Vec<Option<f64>> = Vec::with_capacity(df.height());
+    for row in 0..df.height() {
+        let features: Vec<(Rc<str>, DataTypeValue, f32)> = columns.iter()
+            .filter_map(|(name, datavec)| {
+                let value = parser::datavec_value(datavec, row)?;
+                let weight = magds.feature_weight(name.clone()).unwrap_or(1.0_f32);
+                Some((name.clone(), value, weight))
+            })
+            .collect();
+
+        let predicted = predict_winner(magds, &features, options.fuzzy)
+            .and_then(|(winner, proba)| {
+                let predicted = winner_target(&winner.borrow().explain(), &target)?;
+                Some((predicted.id, proba))
+            });
+        magds.deactivate();
+
+        match predicted {
+            Some((value, proba)) => {
+                predictions.push(Some(value));
+                confidences.push(Some(proba));
+            }
+            None => {
+                log::warn!("cannot predict {target} for row {row}");
+                predictions.push(None);
+                confidences.push(None);
+            }
+        }
+    }
+
+    let prediction_series = match target_data_category {
+        DataCategory::Numerical => {
+            let values: Vec<Option<f64>> = predictions.iter()
+                .map(|value| value.as_ref()?.parse::<f64>().ok())
+                .collect();
+            Series::new(&target, values)
+        }
+        DataCategory::Categorical | DataCategory::Ordinal => {
+            let values: Vec<Option<String>> = predictions.iter()
+                .map(|value| Some(value.as_ref()?.to_string()))
+                .collect();
+            Series::new(&target, values)
+        }
+    };
+    let confidence_series = Series::new(&format!("{target}_confidence"), confidences);
+
+    DataFrame::new(vec![prediction_series, confidence_series]).map_err(|e| e.to_string())
 }
 
-pub fn predict_weighted(
+pub fn weighted_features(
+    features: &[(Rc<str>, DataTypeValue)], weights: &HashMap<Rc<str>, f32>
+) -> Vec<(Rc<str>, DataTypeValue, f32)> {
+    features.iter()
+        .map(|(id, value)| {
+            (id.clone(), value.clone(), weights.get(id).copied().unwrap_or(1.0_f32))
+        })
+        .collect()
+}
+
+pub(crate) fn activate_features(
     magds: &mut MAGDS, 
-    features: Vec<(Rc<str>, DataTypeValue, f32)>,
-    target: Rc<str>,
+    features: &[(Rc<str>, DataTypeValue, f32)],
     fuzzy: bool
-) -> Option<(DataTypeValue, f64)> {
+) -> HashMap<NeuronID, Rc<RefCell<dyn Neuron>>> {
     let mut neurons: HashMap<NeuronID, Rc<RefCell<dyn Neuron>>> = HashMap::new();
 
-    for (id, value, weight) in &features {
-        let sensor = match magds.sensor_search(id.clone(), value) {
-            Some(s) => s,
-            None => {
-                match magds.sensor_data_category(id.clone()) {
-                    Some(DataCategory::Numerical) | Some(DataCategory::Ordinal) => {
-                        if fuzzy {
-                            log::info!("cannot find sensor {id} value {:?}, inserting", value);
-                            match magds.sensor_insert(id.clone(), value) {
-                                Some(s) => s,
-                                None => {
-                                    log::warn!("cannot insert {:?} to {id}, skipping", value);
-                                    continue
-                                }
-                            }
-                        } else {
-                            log::warn!("cannot find sensor {id} for value {:?}, skipping", value);
-                            continue
+    for (id, value, weight) in features {
+        if *weight == 0.0_f32 {
+            log::info!("feature {id} has zero weight, skipping");
+            continue
+        }
+        if fuzzy {
+            if let Some(activated) = magds.sensor_fuzzy_activate(id.clone(), value, *weight) {
+                neurons.extend(activated);
+                continue
+            }
+        }
+
+        if magds.sensor_search(id.clone(), value).is_none() {
+            match magds.sensor_data_category(id.clone()) {
+                Some(DataCategory::Numerical) | Some(DataCategory::Ordinal) => {
+                    if fuzzy {
+                        log::info!(
+                            "cannot find sensor {id} value {:?}, activating nearest", value
+                        );
+                        match magds.sensor_activate_nearest(id.clone(), value, *weight) {
+                            Some(activated) => neurons.extend(activated),
+                            None => log::warn!("cannot activate {:?} in {id}, skipping", value)
                         }
-                    }
-                    _ => {
+                        continue
+                    } else {
                         log::warn!("cannot find sensor {id} for value {:?}, skipping", value);
                         continue
                     }
                 }
+                _ => {
+                    log::warn!("cannot find sensor {id} for value {:?}, skipping", value);
+                    continue
+                }
             }
-        };
-        neurons.extend(sensor.borrow_mut().activate(*weight, fuzzy, true));
+        }
+        match magds.sensor_stimulate(id.clone(), value, *weight, fuzzy, true) {
+            Ok(activated) => neurons.extend(activated),
+            Err(e) => log::warn!("cannot activate sensor {id} value {:?}, error: {e}", value)
+        }
     }
+    magds.apply_inhibition();
 
-    if neurons.is_empty() { return None }
+    neurons
+}
+
+fn activate_features_traced(
+    magds: &mut MAGDS, 
+    features: &[(Rc<str>, DataTypeValue, f32)],
+    fuzzy: bool
+) -> (HashMap<NeuronID, Rc<RefCell<dyn Neuron>>>, HashMap<(NeuronID, NeuronID), f32>) {
+    let traced = magds.trace().is_some();
+    if !traced { magds.start_trace(); }
+    let first_event = magds.trace().map_or(0, |trace| trace.events.len());
+    let mut activations: HashMap<NeuronID, f32> = magds.active_neurons().into_iter().collect();
+
+    let neurons = activate_features(magds, features, fuzzy);
+
+    // every vertical event is recorded right after the neuron accumulated the signal, so the
+    // difference to the previous activation is what the source added under the active policy
+    let mut element_signals: HashMap<(NeuronID, NeuronID), f32> = HashMap::new();
+    if let Some(trace) = magds.trace() {
+        for event in &trace.events[first_event..] {
+            if event.direction != ActivationDirection::Vertical { continue }
+            let previous = activations.insert(event.target.clone(), event.activation)
+                .unwrap_or(0.0);
+            *element_signals.entry((event.target.clone(), event.source.clone()))
+                .or_insert(0.0) += event.activation - previous;
+        }
+    }
+    if !traced { magds.stop_trace(); }
 
-    let neurons_activations: Vec<OrderedFloat<f32>> = neurons.values()
-        .cloned()
-        .map(|neuron| OrderedFloat(neuron.borrow().activation()))
+    (neurons, element_signals)
+}
+
+pub(crate) fn rank_neurons(
+    neurons: &HashMap<NeuronID, Rc<RefCell<dyn Neuron>>>
+) -> Vec<(f32, Rc<RefCell<dyn Neuron>>)> {
+    let mut ranked: Vec<(f32, Rc<RefCell<dyn Neuron>>)> = neurons.values()
+        .map(|neuron| (neuron.borrow().activation(), neuron.clone()))
         .collect();
-    let neurons: Vec<Rc<RefCell<dyn Neuron>>> = neurons.values().cloned().collect();
+    ranked.sort_by(|(a, neuron_a), (b, neuron_b)| {
+        (OrderedFloat(*b), &neuron_a.borrow().id().id)
+            .cmp(&(OrderedFloat(*a), &neuron_b.borrow().id().id))
+    });
+    ranked
+}
 
-    let neurons_sorted: BTreeMap<OrderedFloat<f32>, Rc<RefCell<dyn Neuron>>> 
-        = BTreeMap::from_iter(neurons_activations.into_iter().zip(neurons));
+pub(crate) fn predict_winner(
+    magds: &mut MAGDS, 
+    features: &[(Rc<str>, DataTypeValue, f32)],
+    fuzzy: bool
+) -> Option<(Rc<RefCell<dyn Neuron>>, f64)> {
+    let neurons = activate_features(magds, features, fuzzy);
+    if neurons.is_empty() { return None }
 
-    let (winner_activation, winner) = neurons_sorted.into_iter().next_back()?;
+    let (winner_activation, winner) = rank_neurons(&neurons).into_iter().next()?;
 
     let max_activation = features.len() as f64;
-    let proba = winner_activation.to_f32()? as f64 / max_activation;
+    let proba = winner_activation as f64 / max_activation;
 
-    let predicted_value = winner.borrow().explain_one(target)?;
+    Some((winner, proba))
+}
 
-    Some((predicted_value, proba))
+pub(crate) fn winner_target(
+    explained: &HashMap<NeuronID, Rc<RefCell<dyn Neuron>>>, target: &Rc<str>
+) -> Option<NeuronID> {
+    explained.iter()
+        .filter(|(id, _element)| id.parent_id == *target)
+        .map(|(id, element)| {
+            let element = element.borrow();
+            let value = DataTypeValueStr(&id.id).data_type_value(element.data_type())
+                .and_then(|value| RowValue::new(&value))
+                .unwrap_or_else(|| RowValue::String(id.id.clone()));
+            ((OrderedFloat(element.activation()), element.counter(), Reverse(value)), id)
+        })
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_key, id)| id.clone())
 }
 
-pub fn prediction_score(
-    train: &mut MAGDS, test: &mut MAGDS, target: Rc<str>, fuzzy: bool
-) -> Option<(f64, f64)> {
-    let mut total_proba = 0.0;
-    let mut total_error = 0.0;
-
-    let mut i = 1;
-    for (neuron_id, neuron) in &mut test.neurons {
-        if i % 100 == 0 { println!("prediction iteration: {i}"); }
-        let mut features: Vec<(Rc<str>, DataTypeValue)> = Vec::new();
-        let sensors = neuron.borrow().defining_sensors();
-        let mut test_reference_value = DataTypeValue::Unknown;
-        let mut should_skip = false;
-
-        for (sensor_id, sensor) in sensors {
-            let feature_name: Rc<str> = sensor_id.parent_id.clone();
-            let feature_value_rcstr: Rc<str> = sensor_id.id.clone();
-            let feaure_value_str = DataTypeValueStr(&feature_value_rcstr);
-            let feature_data_type = sensor.borrow().data_type();
-            let feature_value = feaure_value_str.data_type_value(feature_data_type);
-            
-            if *feature_name == *target {
-                match feature_value {
-                    Some(v) => test_reference_value = v,
-                    None => {
-                        log::warn!("target feature {target} is None for {neuron_id}, skipping");
-                        should_skip = true;
-                        break
-                    }
-                };
-            } else {
-                match feature_value {
-                    Some(v) => features.push((feature_name, v)),
-                    None => continue,
-                };
-            }
-        }
+pub(crate) fn neuron_features(
+    neuron: &SimpleNeuron, target: &Rc<str>
+) -> (Vec<(Rc<str>, DataTypeValue)>, Vec<NeuronID>) {
+    let mut features: Vec<(Rc<str>, DataTypeValue)> = Vec::new();
+    let mut targets: Vec<NeuronID> = Vec::new();
 
-        if should_skip { continue }
-        if test_reference_value.is_unknown() { 
-            panic!("test_reference_value shouldn't be unknown");
+    for (sensor_id, sensor) in neuron.defining_sensors() {
+        if sensor_id.parent_id == *target {
+            targets.push(sensor_id);
+            continue
         }
 
-        let (winner_value, winner_proba) = predict(train, &features, target.clone(), fuzzy)?;
-        total_proba += winner_proba;
-        log::debug!("winner_value {:?}, test_reference_value {:?}", winner_value, test_reference_value);
-        total_error += winner_value.distance(&test_reference_value).powf(2.0);
-        train.deactivate();
-
-        i += 1;
+        let feature_value_str = DataTypeValueStr(&sensor_id.id);
+        let feature_data_type = sensor.borrow().data_type();
+        match feature_value_str.data_type_value(feature_data_type) {
+            Some(v) => features.push((sensor_id.parent_id.clone(), v)),
+            None => continue
+        }
     }
 
-    let test_len = test.neurons.len() as f64;
-    let final_proba = total_proba / test_len;
+    (features, targets)
+}
 
-            
-    let target_data_category = train.sensor(target.clone())?.borrow().data_category();
-    match target_data_category {
-        DataCategory::Numerical => {
-            let rmse = (total_error as f64 / test_len).sqrt();
-            Some((rmse, final_proba))
+pub fn prediction_score(
+    train: &mut MAGDS, test: &mut MAGDS, target: Rc<str>, fuzzy: bool
+) -> Option<(f64, f64)> {
+    match evaluation::evaluate(train, test, target, fuzzy)? {
+        EvaluationReport::Classification(report) => {
+            Some((report.accuracy, report.mean_confidence))
         }
-        DataCategory::Categorical | DataCategory::Ordinal => {
-            let accuracy = total_error as f64 / test_len;
-            Some((accuracy, final_proba))
+        EvaluationReport::Regression(report) => {
+            Some((report.rmse, report.mean_confidence))
         }
     }
 }
 
 mod tests {
+    use std::{ collections::HashMap, rc::Rc };
+
     use test_log::test;
 
+    #[allow(unused_imports)]
+    use polars::prelude::*;
+
+    #[allow(unused_imports)]
+    use bionet_common::polars as polars_common;
+
     #[allow(unused_imports)]
     use crate::{
         algorithm::predict,
+        neuron::activation::SaturatingActivation,
         simple::parser
     };
 
@@ -225,4 +410,181 @@ mod tests {
         println!("accuracy: {accuracy} proba: {proba}");
         assert!(accuracy > 0.95);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn predict_explained() {
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+
+        let features = vec![
+            ("sepal.length".into(), 5.8_f64.into()),
+            ("sepal.width".into(), 2.7_f64.into()),
+            ("petal.length".into(), 4.1_f64.into()),
+            ("petal.width".into(), 1.0_f64.into())
+        ];
+        let explained = predict::predict_explained(
+            &mut magds, &features, "variety".into(), false, 2
+        ).unwrap();
+        println!("{:#?}", explained);
+
+        assert_eq!(explained.candidates.len(), 3);
+        assert_eq!(explained.runners_up().len(), 2);
+        let winner = explained.winner();
+        assert!(!winner.contributions.is_empty());
+        assert!(winner.contributions.iter().all(|c| c.exact));
+        assert!(winner.contributions.iter().all(|c| &*c.sensor != "variety"));
+        let total: f32 = winner.contributions.iter().map(|c| c.activation).sum();
+        assert!((total - winner.activation).abs() < 1e-5);
+        assert!(explained.runners_up()[0].activation <= winner.activation);
+        magds.deactivate();
+
+        let policy = SaturatingActivation { threshold: 1.0, saturation: 1.5 };
+        magds.set_activation_policy(Rc::new(policy));
+        magds.set_feature_weights(HashMap::from([("petal.width".into(), 0.5)]));
+        let explained = predict::predict_explained(
+            &mut magds, &features, "variety".into(), false, 0
+        ).unwrap();
+        let winner = explained.winner();
+        assert!(winner.activation <= 1.5);
+        let total: f32 = winner.contributions.iter().map(|c| c.activation).sum();
+        assert!((total - winner.activation).abs() < 1e-5);
+        assert!(magds.trace().is_none());
+    }
+
+    #[test]
+    fn winner_target() {
+        use bionet_common::{ neuron::NeuronID, data::DataType };
+
+        use crate::simple::magds::MAGDS;
+
+        let mut magds = MAGDS::new();
+        magds.create_sensor("tag".into(), DataType::String).unwrap();
+        let neuron = magds.create_neuron(NeuronID::new("1", "tags")).unwrap();
+        for tag in ["c", "b", "a"] {
+            magds.sensor_insert_connected("tag".into(), &tag.to_string().into(), &neuron).unwrap();
+        }
+        let target = |magds: &MAGDS| {
+            let explained = magds.neuron("1", "tags").unwrap().borrow().explain();
+            predict::winner_target(&explained, &"tag".into()).unwrap().id
+        };
+        for _ in 0..10 { assert_eq!(&*target(&magds), "a"); }
+
+        let other = magds.create_neuron(NeuronID::new("2", "tags")).unwrap();
+        magds.sensor_insert_connected("tag".into(), &"b".to_string().into(), &other).unwrap();
+        assert_eq!(&*target(&magds), "b");
+
+        magds.sensor_activate("tag".into(), &"c".to_string().into(), 1.0, false, false).unwrap();
+        assert_eq!(&*target(&magds), "c");
+    }
+
+    #[test]
+    fn predict_df() {
+        let mut magds = parser::magds_from_csv("iris_train", "data/iris_train.csv").unwrap();
+        let df = polars_common::csv_to_dataframe("data/iris_test.csv").unwrap();
+
+        let predictions = predict::predict_df(
+            &mut magds, &df, "variety".into(), &predict::PredictOptions::default()
+        ).unwrap();
+        println!("{predictions}");
+
+        assert_eq!(predictions.height(), df.height());
+        assert_eq!(predictions.get_column_names(), vec!["variety", "variety_confidence"]);
+
+        let expected = df.column("variety").unwrap();
+        let predicted = predictions.column("variety").unwrap();
+        let correct = expected.equal(predicted).into_iter().filter(|x| *x == Some(true)).count();
+        assert!(correct as f64 / df.height() as f64 > 0.95);
+    }
+
+    #[test]
+    fn predict_fuzzy_activation() {
+        use crate::simple::fuzzy::{ FuzzyActivation, FuzzyKernel };
+
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        for sensor in ["sepal.length", "sepal.width", "petal.length", "petal.width"] {
+            let fuzzy_activation = FuzzyActivation {
+                min_activation: 0.1, ..FuzzyActivation::new(FuzzyKernel::Gaussian, 0.2)
+            };
+            magds.set_fuzzy_activation(sensor.into(), fuzzy_activation).unwrap();
+        }
+        let fuzzy_activation = FuzzyActivation::new(FuzzyKernel::Linear, 0.2);
+        assert!(magds.set_fuzzy_activation("variety".into(), fuzzy_activation).is_err());
+
+        let features = vec![
+            ("sepal.length".into(), 5.05_f64.into()),
+            ("sepal.width".into(), 3.45_f64.into()),
+            ("petal.length".into(), 1.45_f64.into()),
+            ("petal.width".into(), 0.25_f64.into())
+        ];
+        let (value, proba) = predict::predict(
+            &mut magds, &features, "variety".into(), true
+        ).unwrap();
+        assert_eq!(value.as_string().map(|v| v.as_str()), Some("Setosa"));
+        assert!(proba > 0.0);
+        assert!(magds.sensor_search("sepal.length".into(), &5.05_f64.into()).is_none());
+
+        magds.deactivate();
+        let neurons = predict::activate_features(
+            &mut magds, &[("sepal.length".into(), 5.8_f64.into(), 1.0)], true
+        );
+        assert!(!neurons.is_empty());
+        let sl = |value: f64| {
+            magds.sensor_search("sepal.length".into(), &value.into()).unwrap().borrow().activation()
+        };
+        assert_eq!(sl(5.8), 1.0);
+        assert!(sl(5.7) < 1.0 && sl(5.7) > 0.0);
+        assert!(sl(5.7) > sl(5.6));
+        assert_eq!(sl(7.9), 0.0);
+    }
+
+    #[test]
+    fn predict_fuzzy_unseen_value() {
+        use crate::simple::trace::ActivationDirection;
+
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let elements_count = |magds: &crate::simple::magds::MAGDS| {
+            magds.sensor("sepal.length".into()).unwrap().borrow().elements().len()
+        };
+        let count = elements_count(&magds);
+
+        let features = vec![
+            ("sepal.length".into(), 5.85_f64.into()),
+            ("sepal.width".into(), 2.7_f64.into()),
+            ("petal.length".into(), 4.15_f64.into()),
+            ("petal.width".into(), 1.0_f64.into())
+        ];
+        magds.start_trace();
+        let (value, _proba) = predict::predict(
+            &mut magds, &features, "variety".into(), true
+        ).unwrap();
+        let trace = magds.stop_trace().unwrap();
+        assert_eq!(value.as_string().map(|v| v.as_str()), Some("Versicolor"));
+        assert_eq!(elements_count(&magds), count);
+        assert!(magds.sensor_search("sepal.length".into(), &5.85_f64.into()).is_none());
+
+        // the unseen value splits one stimulus between the bracketing elements 5.8 and 5.9,
+        // each half attenuated by its distance to 5.85 over the sensor range 4.3..7.9
+        let stimuli: Vec<f32> = trace.events.iter()
+            .filter(|event| {
+                event.direction == ActivationDirection::Stimulus && &*event.sensor == "sepal.length"
+            })
+            .map(|event| event.signal)
+            .collect();
+        let expected = 0.5 * (1.0 - 0.05 / (7.9 - 4.3)) as f32;
+        assert_eq!(stimuli.len(), 2);
+        assert!(stimuli.iter().all(|signal| (signal - expected).abs() < 1e-4));
+
+        // on top of their own stimulus both elements receive horizontal signal from each other
+        let sl = |value: f64| {
+            magds.sensor_search("sepal.length".into(), &value.into()).unwrap().borrow().activation()
+        };
+        assert!((sl(5.8) - sl(5.9)).abs() < 1e-5);
+        assert!(sl(5.8) > expected && sl(5.8) < 2.0 * expected);
+        assert!(sl(5.7) < sl(5.8));
+
+        magds.deactivate();
+        let mut magds_test = parser::magds_from_csv("iris_test", "data/iris_test.csv").unwrap();
+        let count = elements_count(&magds);
+        predict::prediction_score(&mut magds, &mut magds_test, "variety".into(), true).unwrap();
+        assert_eq!(elements_count(&magds), count);
+    }
+}
@@ -0,0 +1,238 @@
+use std::{
+    cell::RefCell,
+    collections::{ HashMap, HashSet },
+    ops::Bound,
+    rc::Rc
+};
+
+use bionet_common::{
+    neuron::{ Neuron, NeuronID },
+    data::DataTypeValue
+};
+
+use crate::simple::magds::MAGDS;
+
+#[derive(Debug, Clone)]
+pub enum Query {
+    Eq(Rc<str>, DataTypeValue),
+    Range(Rc<str>, Bound<DataTypeValue>, Bound<DataTypeValue>),
+    In(Rc<str>, Vec<DataTypeValue>),
+    And(Vec<Query>),
+    Or(Vec<Query>),
+    Not(Box<Query>)
+}
+
+#[derive(Clone)]
+pub struct QueryRecord {
+    pub neuron: NeuronID,
+    pub elements: HashMap<NeuronID, Rc<RefCell<dyn Neuron>>>
+}
+
+impl Query {
+    pub fn eq(sensor: &str, value: DataTypeValue) -> Query { Query::Eq(sensor.into(), value) }
+
+    pub fn less_than(sensor: &str, value: DataTypeValue) -> Query {
+        Query::Range(sensor.into(), Bound::Unbounded, Bound::Excluded(value))
+    }
+
+    pub fn greater_than(sensor: &str, value: DataTypeValue) -> Query {
+        Query::Range(sensor.into(), Bound::Excluded(value), Bound::Unbounded)
+    }
+
+    pub fn between(sensor: &str, lower: DataTypeValue, upper: DataTypeValue) -> Query {
+        Query::Range(sensor.into(), Bound::Included(lower), Bound::Included(upper))
+    }
+
+    pub fn is_in(sensor: &str, values: Vec<DataTypeValue>) -> Query {
+        Query::In(sensor.into(), values)
+    }
+
+    pub fn and(self, other: Query) -> Query {
+        match self {
+            Query::And(mut queries) => { queries.push(other); Query::And(queries) }
+            query => Query::And(vec![query, other])
+        }
+    }
+
+    pub fn or(self, other: Query) -> Query {
+        match self {
+            Query::Or(mut queries) => { queries.push(other); Query::Or(queries) }
+            query => Query::Or(vec![query, other])
+        }
+    }
+
+    pub fn not(self) -> Query { Query::Not(Box::new(self)) }
+
+    pub fn evaluate(&self, magds: &MAGDS) -> Result<HashSet<NeuronID>, String> {
+        match self {
+            Query::Eq(sensor, value) => {
+                sensor_neurons(magds, sensor, Bound::Included(value), Bound::Included(value))
+            }
+            Query::Range(sensor, lower, upper) => {
+                sensor_neurons(magds, sensor, lower.as_ref(), upper.as_ref())
+            }
+            Query::In(sensor, values) => {
+                let mut result = HashSet::new();
+                for value in values {
+                    result.extend(
+                        sensor_neurons(
+                            magds, sensor, Bound::Included(value), Bound::Included(value)
+                        )?
+                    );
+                }
+                Ok(result)
+            }
+            Query::And(queries) => {
+                let mut result: Option<HashSet<NeuronID>> = None;
+                for query in queries {
+                    let neurons = query.evaluate(magds)?;
+                    result = Some(match result {
+                        Some(result) => &result & &neurons,
+                        None => neurons
+                    });
+                    if result.as_ref().unwrap().is_empty() { break }
+                }
+                Ok(result.unwrap_or_else(|| magds.neurons.keys().cloned().collect()))
+            }
+            Query::Or(queries) => {
+                let mut result = HashSet::new();
+                for query in queries { result.extend(query.evaluate(magds)?); }
+                Ok(result)
+            }
+            Query::Not(query) => {
+                // a neuron without a value for one of the queried sensors neither matches nor
+                // fails the query, so the complement only covers neurons defined on all of them
+                let mut defined: Option<HashSet<NeuronID>> = None;
+                for sensor in query.sensors() {
+                    let neurons = sensor_neurons(
+                        magds, &sensor, Bound::Unbounded, Bound::Unbounded
+                    )?;
+                    defined = Some(match defined {
+                        Some(defined) => &defined & &neurons,
+                        None => neurons
+                    });
+                }
+                let excluded = query.evaluate(magds)?;
+                Ok(
+                    defined.unwrap_or_default().into_iter()
+                        .filter(|id| !excluded.contains(id))
+                        .collect()
+                )
+            }
+        }
+    }
+
+    fn sensors(&self) -> HashSet<Rc<str>> {
+        match self {
+            Query::Eq(sensor, _) | Query::Range(sensor, _, _) | Query::In(sensor, _) => {
+                HashSet::from([sensor.clone()])
+            }
+            Query::And(queries) | Query::Or(queries) => {
+                queries.iter().flat_map(|query| query.sensors()).collect()
+            }
+            Query::Not(query) => query.sensors()
+        }
+    }
+
+    pub fn records(&self, magds: &MAGDS) -> Result<Vec<QueryRecord>, String> {
+        let mut neurons: Vec<NeuronID> = self.evaluate(magds)?.into_iter().collect();
+        neurons.sort_by(|a, b| (&a.parent_id, &a.id).cmp(&(&b.parent_id, &b.id)));
+        Ok(
+            neurons.into_iter()
+                .filter_map(|id| {
+                    let elements = magds.neurons.get(&id)?.borrow().explain();
+                    Some(QueryRecord { neuron: id, elements })
+                })
+                .collect()
+        )
+    }
+}
+
+fn sensor_neurons(
+    magds: &MAGDS, sensor: &Rc<str>, lower: Bound<&DataTypeValue>, upper: Bound<&DataTypeValue>
+) -> Result<HashSet<NeuronID>, String> {
+    Ok(magds.range_query_bounds(sensor.clone(), lower, upper)?.neurons.into_keys().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use test_log::test;
+
+    use bionet_common::{
+        neuron::NeuronID,
+        data::{ DataType, DataTypeValue }
+    };
+
+    use crate::simple::{ magds::MAGDS, parser };
+
+    use super::Query;
+
+    #[test]
+    fn evaluate() {
+        let magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let setosa: DataTypeValue = "Setosa".to_string().into();
+
+        let setosa_neurons = Query::eq("variety", setosa.clone()).evaluate(&magds).unwrap();
+        assert_eq!(setosa_neurons.len(), 49);
+
+        let query = Query::eq("variety", setosa.clone())
+            .and(Query::less_than("petal.length", 2.0_f64.into()))
+            .and(Query::between("sepal.width", 3.0_f64.into(), 3.5_f64.into()).not());
+        let neurons = query.evaluate(&magds).unwrap();
+        assert!(!neurons.is_empty());
+        assert!(neurons.is_subset(&setosa_neurons));
+
+        for record in query.records(&magds).unwrap() {
+            let value = |sensor: &str| {
+                record.elements.keys()
+                    .find(|id| &*id.parent_id == sensor)
+                    .map(|id| id.id.parse::<f64>().unwrap())
+            };
+            assert!(value("petal.length").unwrap() < 2.0);
+            assert!(!(3.0..=3.5).contains(&value("sepal.width").unwrap()));
+        }
+
+        let query = Query::is_in(
+            "variety", vec![setosa.clone(), "Virginica".to_string().into()]
+        );
+        let either = query.evaluate(&magds).unwrap();
+        let or = Query::eq("variety", setosa)
+            .or(Query::eq("variety", "Virginica".to_string().into()))
+            .evaluate(&magds)
+            .unwrap();
+        assert_eq!(either, or);
+        assert_eq!(
+            either.len() + Query::Not(Box::new(query)).evaluate(&magds).unwrap().len(),
+            magds.neurons.len()
+        );
+
+        assert!(Query::eq("unknown", 1.0_f64.into()).evaluate(&magds).is_err());
+        let query = Query::eq("petal.length", "Setosa".to_string().into());
+        assert!(query.evaluate(&magds).is_err());
+    }
+
+    #[test]
+    fn not_defined() {
+        let mut magds = MAGDS::new();
+        magds.create_sensor("a".into(), DataType::I32).unwrap();
+        magds.create_sensor("b".into(), DataType::I32).unwrap();
+        let values = [("1", "p", Some(1)), ("2", "p", Some(2)), ("3", "p", None), ("4", "q", None)];
+        for (id, parent, value) in values {
+            let neuron = magds.create_neuron(NeuronID::new(id, parent)).unwrap();
+            if let Some(value) = value {
+                magds.sensor_insert_connected("a".into(), &value.into(), &neuron).unwrap();
+            }
+            magds.sensor_insert_connected("b".into(), &0.into(), &neuron).unwrap();
+        }
+
+        let not_one = Query::eq("a", 1.into()).not().evaluate(&magds).unwrap();
+        assert_eq!(not_one, HashSet::from([NeuronID::new("2", "p")]));
+
+        let not_zero = Query::eq("b", 0.into()).not().evaluate(&magds).unwrap();
+        assert!(not_zero.is_empty());
+        let not_both = Query::eq("a", 2.into()).or(Query::eq("b", 1.into())).not();
+        assert_eq!(not_both.evaluate(&magds).unwrap(), HashSet::from([NeuronID::new("1", "p")]));
+    }
+}
@@ -2,11 +2,15 @@
 #![feature(generic_const_exprs)] // TODO: check if stable versions allows for const generic parameters not only standalone in constexpr
 #![feature(map_try_insert)]
 
+extern crate self as magds;
+
 pub mod neuron;
 pub mod dynamic;
 pub mod simple;
 pub mod algorithm;
 
+pub use magds_derive::MagdsRecord;
+
 #[cfg(test)]
 mod tests {
     
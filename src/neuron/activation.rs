@@ -0,0 +1,69 @@
+pub trait ActivationPolicy {
+    fn accumulate(&self, activation: f32, signal: f32, defining_sensors: usize) -> f32;
+
+    fn output(&self, activation: f32, defined_neurons: usize) -> f32 {
+        if defined_neurons == 0 { 0.0f32 } else { activation / defined_neurons as f32 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultActivation;
+
+impl ActivationPolicy for DefaultActivation {
+    fn accumulate(&self, activation: f32, signal: f32, _defining_sensors: usize) -> f32 {
+        activation + signal
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NormalizedActivation;
+
+impl ActivationPolicy for NormalizedActivation {
+    fn accumulate(&self, activation: f32, signal: f32, defining_sensors: usize) -> f32 {
+        if defining_sensors == 0 { return activation + signal }
+        activation + signal / defining_sensors as f32
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SaturatingActivation {
+    pub threshold: f32,
+    pub saturation: f32
+}
+
+impl Default for SaturatingActivation {
+    fn default() -> Self { SaturatingActivation { threshold: 0.0f32, saturation: 1.0f32 } }
+}
+
+impl ActivationPolicy for SaturatingActivation {
+    fn accumulate(&self, activation: f32, signal: f32, _defining_sensors: usize) -> f32 {
+        (activation + signal).min(self.saturation)
+    }
+
+    fn output(&self, activation: f32, defined_neurons: usize) -> f32 {
+        if activation < self.threshold || defined_neurons == 0 { return 0.0f32 }
+        activation / defined_neurons as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ActivationPolicy, DefaultActivation, NormalizedActivation, SaturatingActivation
+    };
+
+    #[test]
+    fn activation_policies() {
+        assert_eq!(DefaultActivation.accumulate(0.5, 1.0, 4), 1.5);
+        assert_eq!(DefaultActivation.output(1.5, 3), 0.5);
+        assert_eq!(DefaultActivation.output(1.5, 0), 0.0);
+
+        assert_eq!(NormalizedActivation.accumulate(0.5, 1.0, 4), 0.75);
+        assert_eq!(NormalizedActivation.accumulate(0.5, 1.0, 0), 1.5);
+
+        let saturating = SaturatingActivation { threshold: 0.5, saturation: 1.0 };
+        assert_eq!(saturating.accumulate(0.5, 1.0, 4), 1.0);
+        assert_eq!(saturating.output(0.4, 2), 0.0);
+        assert_eq!(saturating.output(1.0, 2), 0.5);
+    }
+}
@@ -1 +1,2 @@
-pub mod simple_neuron;
\ No newline at end of file
+pub mod simple_neuron;
+pub mod activation;
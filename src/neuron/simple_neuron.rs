@@ -1,7 +1,7 @@
 use std::{
     rc::{ Rc, Weak },
     cell::RefCell,
-    collections::HashMap,
+    collections::{ HashMap, HashSet },
     fmt::{ Display, Formatter, Result as FmtResult },
     marker::PhantomData
 };
@@ -25,10 +25,18 @@ use bionet_common::{
 
 use asa_graphs::neural::element::Element;
 
+use crate::simple::trace::{ ActivationTrace, ActivationEvent, ActivationDirection };
+
+use super::activation::{ ActivationPolicy, DefaultActivation };
+
 pub struct SimpleNeuron {
     pub id: NeuronID,
     pub activation: f32,
+    pub counter: usize,
+    pub(crate) activation_policy: Rc<dyn ActivationPolicy>,
     pub(crate) self_ptr: Weak<RefCell<SimpleNeuron>>,
+    pub(crate) defining_sensor_ids: HashSet<Rc<str>>,
+    pub(crate) trace: Option<Rc<RefCell<ActivationTrace>>>,
     pub(crate) definitions_from_self: 
         HashMap<ConnectionID, Rc<RefCell<dyn Connection<From = dyn Neuron, To = dyn Neuron>>>>,
     pub(crate) definitions_to_self: 
@@ -42,7 +50,11 @@ impl SimpleNeuron {
                 SimpleNeuron {
                     id,
                     activation: 0.0f32,
+                    counter: 1usize,
+                    activation_policy: Rc::new(DefaultActivation),
                     self_ptr: Weak::new(), 
+                    defining_sensor_ids: HashSet::new(),
+                    trace: None,
                     definitions_from_self: HashMap::new(),
                     definitions_to_self: HashMap::new()
                 }
@@ -87,6 +99,18 @@ impl SimpleNeuron {
         neurons
     }
 
+    pub(crate) fn accumulate(&self, activation: f32, signal: f32) -> f32 {
+        self.activation_policy.accumulate(activation, signal, self.defining_sensor_ids.len())
+    }
+
+    pub(crate) fn output(&self, activation: f32, defined_neurons: usize) -> f32 {
+        self.activation_policy.output(activation, defined_neurons)
+    }
+
+    pub fn set_activation_policy(&mut self, policy: Rc<dyn ActivationPolicy>) {
+        self.activation_policy = policy;
+    }
+
     pub fn id(&self) -> NeuronID { self.id.clone() }
 
     pub fn activation(&self) -> f32 { self.activation }
@@ -95,7 +119,7 @@ impl SimpleNeuron {
 
     pub fn data_type(&self) -> DataType { DataType::Unknown }
 
-    pub fn counter(&self) -> usize { 1usize }
+    pub fn counter(&self) -> usize { self.counter }
 
     pub fn explain(&self) -> HashMap<NeuronID, Rc<RefCell<dyn Neuron>>> {
         self.defining_sensors()
@@ -116,20 +140,38 @@ impl SimpleNeuron {
     pub fn activate(
         &mut self, signal: f32, propagate_horizontal: bool, propagate_vertical: bool
     ) -> HashMap<NeuronID, Rc<RefCell<dyn Neuron>>> {
-        self.activation += signal;
+        self.activation = self.accumulate(self.activation, signal);
+        if let Some(trace) = &self.trace {
+            let mut trace = trace.borrow_mut();
+            if let Some(source) = trace.source.clone() {
+                let step = trace.steps();
+                trace.events.push(ActivationEvent {
+                    step,
+                    sensor: source.parent_id.clone(),
+                    source,
+                    target: self.id(),
+                    signal,
+                    activation: self.activation,
+                    direction: ActivationDirection::Vertical
+                });
+            }
+        }
 
         let mut neurons = self.defined_neurons();
         if propagate_vertical {
+            let output_signal = self.output(self.activation, neurons.len());
+            if output_signal == 0.0f32 { return neurons }
+            let previous_source = self.trace.as_ref()
+                .and_then(|trace| trace.borrow_mut().source.replace(self.id()));
             for (_id, neuron) in &neurons.clone() {
                 if !neuron.borrow().is_sensor() {
-                    let output_signal = self.activation / self.defined_neurons().len() as f32;
-                    neurons.extend(
-                        neuron.borrow_mut().activate(
-                            output_signal, propagate_horizontal, propagate_vertical
-                        )
+                    let activated = neuron.borrow_mut().activate(
+                        output_signal, propagate_horizontal, propagate_vertical
                     );
+                    neurons.extend(activated);
                 }
             }
+            if let Some(trace) = &self.trace { trace.borrow_mut().source = previous_source; }
         }
 
         neurons
@@ -240,7 +282,12 @@ impl NeuronConnect for SimpleNeuron {
                     from.clone(),
                     self.self_ptr.upgrade().unwrap() as Rc<RefCell<dyn Neuron>>
                 )));
-                let connection_id = ConnectionID { from: from.borrow().id(), to: self.id() };
+                let (from_id, from_sensor) = {
+                    let from = from.borrow();
+                    (from.id(), from.is_sensor())
+                };
+                if from_sensor { self.defining_sensor_ids.insert(from_id.parent_id.clone()); }
+                let connection_id = ConnectionID { from: from_id, to: self.id() };
                 self.definitions_to_self.insert(connection_id, connection.clone());
                 Ok(connection)
             },
@@ -259,6 +306,10 @@ impl NeuronConnect for SimpleNeuron {
             ConnectionKind::Defining => {
                 let from_neuron_ptr = from_connection.borrow().from().as_ptr();
                 let from_neuorn_id = unsafe { (&*from_neuron_ptr).id() };
+                let from_sensor = unsafe { (&*from_neuron_ptr).is_sensor() };
+                if from_sensor {
+                    self.defining_sensor_ids.insert(from_neuorn_id.parent_id.clone());
+                }
                 let connection_id = ConnectionID { from: from_neuorn_id, to: self.id() };
                 self.definitions_to_self.insert(connection_id, from_connection.clone());
                 Ok(from_connection.clone())
@@ -573,6 +624,7 @@ mod tests {
         assert_eq!(neuron_1.borrow().defined_neurons().len(), 0);
         assert_eq!(neuron_1.borrow().defining_neurons().len(), 0);
         assert_eq!(neuron_1.borrow().defining_sensors().len(), 1);
+        assert_eq!(neuron_1.borrow().defining_sensor_ids.len(), 1);
         assert_eq!(neuron_2.borrow().defined_neurons().len(), 0);
         let connection_1 = connection_1.unwrap();
         let connection_2 = neuron_2.borrow_mut().connect_to_connection(connection_1.clone());
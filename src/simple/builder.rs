@@ -0,0 +1,590 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{ Display, Formatter, Result as FmtResult },
+    mem,
+    path::Path,
+    rc::Rc
+};
+
+use polars::prelude::{ DataFrame, DataType as PolarsDataType };
+
+use bionet_common::{
+    neuron::NeuronID,
+    data::{ DataType, DataTypeValue, DataCategory },
+    polars::{ self as polars_common, DataVecOption }
+};
+
+use crate::neuron::activation::ActivationPolicy;
+
+use super::{
+    magds::MAGDS,
+    parser,
+    fuzzy::FuzzyActivation,
+    record::MagdsRecord
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError {
+    NoSensors,
+    MissingColumn(Rc<str>),
+    UnsupportedDataType { sensor: Rc<str>, data_type: String },
+    TypeMismatch { sensor: Rc<str>, row: usize, expected: String, found: String },
+    MissingValue { sensor: Rc<str>, row: usize },
+    InvalidList { sensor: Rc<str>, row: usize, message: String },
+    DuplicateNeuron(NeuronID),
+    Sensor { sensor: Rc<str>, message: String },
+    Polars(String),
+    Csv(String)
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            BuildError::NoSensors => write!(f, "no sensors to build"),
+            BuildError::MissingColumn(sensor) => {
+                write!(f, "declared sensor {sensor} is missing in the data")
+            }
+            BuildError::UnsupportedDataType { sensor, data_type } => {
+                write!(f, "sensor {sensor} has unsupported data type {data_type}")
+            }
+            BuildError::TypeMismatch { sensor, row, expected, found } => {
+                write!(f, "sensor {sensor} row {row}: expected {expected}, found {found}")
+            }
+            BuildError::MissingValue { sensor, row } => {
+                write!(f, "sensor {sensor} row {row}: missing value")
+            }
+            BuildError::InvalidList { sensor, row, message } => {
+                write!(f, "sensor {sensor} row {row}: {message}")
+            }
+            BuildError::DuplicateNeuron(id) => write!(f, "duplicate neuron id {id}"),
+            BuildError::Sensor { sensor, message } => write!(f, "sensor {sensor}: {message}"),
+            BuildError::Polars(message) => write!(f, "polars error: {message}"),
+            BuildError::Csv(message) => write!(f, "csv error: {message}")
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+#[derive(Debug, Clone, Default)]
+pub enum MissingPolicy {
+    #[default]
+    Skip,
+    Reject,
+    Fill(DataTypeValue)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SensorOptions {
+    pub data_type: Option<DataType>,
+    pub data_category: Option<DataCategory>,
+    pub missing: Option<MissingPolicy>,
+    pub fuzzy: Option<FuzzyActivation>,
+    pub parse_lists: Option<bool>
+}
+
+struct Column {
+    name: Rc<str>,
+    data_type: DataType,
+    values: Vec<Vec<DataTypeValue>>
+}
+
+pub struct MagdsBuilder {
+    name: Rc<str>,
+    sensors: Vec<(Rc<str>, SensorOptions)>,
+    only_declared: bool,
+    neuron_id_column: Option<Rc<str>>,
+    parse_lists: bool,
+    missing: MissingPolicy,
+    collapse_duplicates: bool,
+    activation_policy: Option<Rc<dyn ActivationPolicy>>
+}
+
+impl MagdsBuilder {
+    pub fn new(name: &str) -> MagdsBuilder {
+        MagdsBuilder {
+            name: name.into(),
+            sensors: Vec::new(),
+            only_declared: false,
+            neuron_id_column: None,
+            parse_lists: true,
+            missing: MissingPolicy::Skip,
+            collapse_duplicates: false,
+            activation_policy: None
+        }
+    }
+
+    pub fn sensor(mut self, name: &str, options: SensorOptions) -> MagdsBuilder {
+        self.sensors.retain(|(id, _)| &**id != name);
+        self.sensors.push((name.into(), options));
+        self
+    }
+
+    pub fn only_declared_sensors(mut self, only_declared: bool) -> MagdsBuilder {
+        self.only_declared = only_declared;
+        self
+    }
+
+    pub fn neuron_id_column(mut self, column: &str) -> MagdsBuilder {
+        self.neuron_id_column = Some(column.into());
+        self
+    }
+
+    pub fn parse_lists(mut self, parse_lists: bool) -> MagdsBuilder {
+        self.parse_lists = parse_lists;
+        self
+    }
+
+    pub fn missing(mut self, missing: MissingPolicy) -> MagdsBuilder {
+        self.missing = missing;
+        self
+    }
+
+    pub fn collapse_duplicates(mut self, collapse_duplicates: bool) -> MagdsBuilder {
+        self.collapse_duplicates = collapse_duplicates;
+        self
+    }
+
+    pub fn activation_policy(mut self, policy: Rc<dyn ActivationPolicy>) -> MagdsBuilder {
+        self.activation_policy = Some(policy);
+        self
+    }
+
+    pub fn build_from_csv(&self, file_path: &str) -> Result<MAGDS, BuildError> {
+        let path = Path::new(file_path);
+        if !path.is_file() || !file_path.ends_with(".csv") {
+            return Err(BuildError::Csv(format!("{file_path} is not a csv file")))
+        }
+        let df = polars_common::csv_to_dataframe(file_path)
+            .map_err(|e| BuildError::Csv(e.to_string()))?;
+        self.build_from_df(&df)
+    }
+
+    pub fn build_from_df(&self, df: &DataFrame) -> Result<MAGDS, BuildError> {
+        let mut columns: Vec<Column> = Vec::new();
+        let mut ids: Option<Vec<Rc<str>>> = None;
+
+        for series in df.get_columns() {
+            let name: Rc<str> = series.name().into();
+            if self.neuron_id_column.as_ref() == Some(&name) {
+                let series = series.cast(&PolarsDataType::Utf8)
+                    .map_err(|e| BuildError::Polars(e.to_string()))?;
+                let utf8 = series.utf8().map_err(|e| BuildError::Polars(e.to_string()))?;
+                ids = Some(
+                    utf8.into_iter().enumerate()
+                        .map(|(i, id)| id.map(Rc::from).unwrap_or((i + 1).to_string().into()))
+                        .collect()
+                );
+                if self.options(&name).is_none() { continue }
+            }
+
+            let options = self.options(&name);
+            if self.only_declared && options.is_none() { continue }
+
+            let series = match options.and_then(|o| o.data_type.clone()) {
+                Some(data_type) => {
+                    let polars_type = polars_data_type(&data_type).ok_or(
+                        BuildError::UnsupportedDataType {
+                            sensor: name.clone(), data_type: format!("{:?}", data_type)
+                        }
+                    )?;
+                    series.cast(&polars_type).map_err(|e| BuildError::Polars(e.to_string()))?
+                }
+                None => series.clone()
+            };
+            let datavec = polars_common::series_to_datavec(&series)
+                .map_err(|e| BuildError::Polars(e.to_string()))?;
+            let data_type = datavec_data_type(&datavec).ok_or(
+                BuildError::UnsupportedDataType {
+                    sensor: name.clone(), data_type: format!("{:?}", series.dtype())
+                }
+            )?;
+
+            let parse_lists = options.and_then(|o| o.parse_lists).unwrap_or(self.parse_lists);
+            let values = (0..df.height())
+                .map(|row| -> Result<Vec<DataTypeValue>, BuildError> {
+                    let value = match parser::datavec_value(&datavec, row) {
+                        Some(value) => value,
+                        None => return Ok(Vec::new())
+                    };
+                    let list = match value.as_string().filter(|_| parse_lists) {
+                        Some(value) => parser::parse_list(value).map_err(|message| {
+                            BuildError::InvalidList { sensor: name.clone(), row, message }
+                        })?,
+                        None => None
+                    };
+                    match list {
+                        Some(list) => Ok(list.into_iter().map(DataTypeValue::from).collect()),
+                        None => Ok(vec![value])
+                    }
+                })
+                .collect::<Result<_, _>>()?;
+            columns.push(Column { name, data_type, values });
+        }
+
+        self.build(columns, ids, df.height())
+    }
+
+    pub fn build_from_rows<I, R>(&self, rows: I) -> Result<MAGDS, BuildError>
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator<Item = (Rc<str>, Option<DataTypeValue>)>
+    {
+        let mut columns: Vec<Column> = Vec::new();
+        let mut column_index: HashMap<Rc<str>, usize> = HashMap::new();
+        let mut rows_count = 0;
+
+        for (row, entries) in rows.into_iter().enumerate() {
+            rows_count += 1;
+            for (name, value) in entries {
+                let options = self.options(&name);
+                if self.only_declared && options.is_none() { continue }
+
+                let index = match column_index.get(&name) {
+                    Some(index) => *index,
+                    None => {
+                        let data_type = match options.and_then(|o| o.data_type.clone()) {
+                            Some(data_type) => data_type,
+                            None => match value.as_ref().map(value_data_type) {
+                                Some(data_type) => data_type,
+                                None => continue
+                            }
+                        };
+                        columns.push(Column { name: name.clone(), data_type, values: Vec::new() });
+                        column_index.insert(name.clone(), columns.len() - 1);
+                        columns.len() - 1
+                    }
+                };
+
+                let column = &mut columns[index];
+                column.values.resize(row + 1, Vec::new());
+                if let Some(value) = value {
+                    check_data_type(&column.name, row, &column.data_type, &value)?;
+                    column.values[row].push(value);
+                }
+            }
+        }
+        for column in &mut columns { column.values.resize(rows_count, Vec::new()); }
+
+        self.build(columns, None, rows_count)
+    }
+
+    pub fn build_from_records<'a, T, I>(&self, records: I) -> Result<MAGDS, BuildError>
+    where
+        T: MagdsRecord + 'a,
+        I: IntoIterator<Item = &'a T>
+    {
+        let rows = records.into_iter().map(|record| {
+            let mut entries: Vec<(Rc<str>, Option<DataTypeValue>)> = Vec::new();
+            for (sensor, _data_type, values) in record.fields() {
+                if values.is_empty() { entries.push((sensor.clone(), None)); }
+                entries.extend(values.into_iter().map(|value| (sensor.clone(), Some(value))));
+            }
+            entries
+        });
+        self.build_from_rows(rows)
+    }
+
+    fn options(&self, name: &Rc<str>) -> Option<&SensorOptions> {
+        self.sensors.iter().find(|(id, _)| id == name).map(|(_, options)| options)
+    }
+
+    fn build(
+        &self, mut columns: Vec<Column>, ids: Option<Vec<Rc<str>>>, rows: usize
+    ) -> Result<MAGDS, BuildError> {
+        for (name, _options) in &self.sensors {
+            if !columns.iter().any(|column| column.name == *name) {
+                return Err(BuildError::MissingColumn(name.clone()))
+            }
+        }
+        if columns.is_empty() { return Err(BuildError::NoSensors) }
+
+        for column in &mut columns {
+            let missing = self.options(&column.name)
+                .and_then(|o| o.missing.clone())
+                .unwrap_or(self.missing.clone());
+            for (row, values) in column.values.iter_mut().enumerate() {
+                if !values.is_empty() { continue }
+                match &missing {
+                    MissingPolicy::Skip => {}
+                    MissingPolicy::Reject => {
+                        return Err(BuildError::MissingValue { sensor: column.name.clone(), row })
+                    }
+                    MissingPolicy::Fill(value) => {
+                        check_data_type(&column.name, row, &column.data_type, value)?;
+                        values.push(value.clone());
+                    }
+                }
+            }
+        }
+
+        let mut magds = MAGDS::new();
+        if let Some(policy) = &self.activation_policy {
+            magds.set_activation_policy(policy.clone());
+        }
+
+        for column in &columns {
+            let sensor_error = |message: String| BuildError::Sensor {
+                sensor: column.name.clone(), message
+            };
+            magds.create_sensor(column.name.clone(), column.data_type.clone())
+                .ok_or(sensor_error("cannot create sensor".to_string()))?;
+            let options = match self.options(&column.name) {
+                Some(options) => options,
+                None => continue
+            };
+            if let Some(data_category) = &options.data_category {
+                magds.set_sensor_data_category(column.name.clone(), data_category.clone())
+                    .map_err(sensor_error)?;
+            }
+            if let Some(fuzzy) = options.fuzzy {
+                magds.set_fuzzy_activation(column.name.clone(), fuzzy).map_err(sensor_error)?;
+            }
+        }
+
+        let duplicates = if self.collapse_duplicates {
+            parser::duplicate_rows(rows, |row| {
+                columns.iter().map(|column| column.values[row].clone()).collect()
+            })
+        } else {
+            vec![None; rows]
+        };
+
+        for row in 0..rows {
+            let id = self.neuron_id(&ids, row);
+
+            // element counters count distinct records, row multiplicity is kept by the neuron
+            if let Some(original) = duplicates[row] {
+                let neuron = magds.neuron_from_id(&self.neuron_id(&ids, original))
+                    .ok_or(BuildError::DuplicateNeuron(id))?;
+                neuron.borrow_mut().counter += 1;
+                continue
+            }
+            let neuron = magds.create_neuron(id.clone()).ok_or(BuildError::DuplicateNeuron(id))?;
+
+            for column in &columns {
+                for value in &column.values[row] {
+                    magds.sensor_insert_connected(column.name.clone(), value, &neuron)
+                        .map_err(|message| BuildError::Sensor {
+                            sensor: column.name.clone(), message
+                        })?;
+                }
+            }
+        }
+
+        Ok(magds)
+    }
+
+    fn neuron_id(&self, ids: &Option<Vec<Rc<str>>>, row: usize) -> NeuronID {
+        let id = match ids {
+            Some(ids) => ids[row].clone(),
+            None => (row + 1).to_string().into()
+        };
+        NeuronID { id, parent_id: self.name.clone() }
+    }
+}
+
+fn polars_data_type(data_type: &DataType) -> Option<PolarsDataType> {
+    match data_type {
+        DataType::Bool => Some(PolarsDataType::Boolean),
+        DataType::U8 => Some(PolarsDataType::UInt8),
+        DataType::U16 => Some(PolarsDataType::UInt16),
+        DataType::U32 => Some(PolarsDataType::UInt32),
+        DataType::U64 => Some(PolarsDataType::UInt64),
+        DataType::I8 => Some(PolarsDataType::Int8),
+        DataType::I16 => Some(PolarsDataType::Int16),
+        DataType::I32 => Some(PolarsDataType::Int32),
+        DataType::I64 => Some(PolarsDataType::Int64),
+        DataType::F32 => Some(PolarsDataType::Float32),
+        DataType::F64 => Some(PolarsDataType::Float64),
+        DataType::String => Some(PolarsDataType::Utf8),
+        _ => None
+    }
+}
+
+fn datavec_data_type(datavec: &DataVecOption) -> Option<DataType> {
+    match datavec {
+        DataVecOption::Unknown => None,
+        DataVecOption::BoolVec(_) => Some(DataType::Bool),
+        DataVecOption::UInt8Vec(_) => Some(DataType::U8),
+        DataVecOption::UInt16Vec(_) => Some(DataType::U16),
+        DataVecOption::UInt32Vec(_) => Some(DataType::U32),
+        DataVecOption::UInt64Vec(_) => Some(DataType::U64),
+        DataVecOption::Int8Vec(_) => Some(DataType::I8),
+        DataVecOption::Int16Vec(_) => Some(DataType::I16),
+        DataVecOption::Int32Vec(_) => Some(DataType::I32),
+        DataVecOption::Int64Vec(_) => Some(DataType::I64),
+        DataVecOption::Float32Vec(_) => Some(DataType::F32),
+        DataVecOption::Float64Vec(_) => Some(DataType::F64),
+        DataVecOption::Utf8Vec(_) => Some(DataType::String)
+    }
+}
+
+fn value_data_type(value: &DataTypeValue) -> DataType {
+    match value {
+        DataTypeValue::Bool(_) => DataType::Bool,
+        DataTypeValue::U8(_) => DataType::U8,
+        DataTypeValue::U16(_) => DataType::U16,
+        DataTypeValue::U32(_) => DataType::U32,
+        DataTypeValue::U64(_) => DataType::U64,
+        DataTypeValue::U128(_) => DataType::U128,
+        DataTypeValue::USize(_) => DataType::USize,
+        DataTypeValue::I8(_) => DataType::I8,
+        DataTypeValue::I16(_) => DataType::I16,
+        DataTypeValue::I32(_) => DataType::I32,
+        DataTypeValue::I64(_) => DataType::I64,
+        DataTypeValue::I128(_) => DataType::I128,
+        DataTypeValue::ISize(_) => DataType::ISize,
+        DataTypeValue::F32(_) => DataType::F32,
+        DataTypeValue::F64(_) => DataType::F64,
+        DataTypeValue::RcStr(_) => DataType::RcStr,
+        DataTypeValue::String(_) => DataType::String,
+        _ => DataType::Unknown
+    }
+}
+
+fn check_data_type(
+    sensor: &Rc<str>, row: usize, data_type: &DataType, value: &DataTypeValue
+) -> Result<(), BuildError> {
+    let value_type = value_data_type(value);
+    if mem::discriminant(&value_type) == mem::discriminant(data_type) { return Ok(()) }
+    Err(BuildError::TypeMismatch {
+        sensor: sensor.clone(),
+        row,
+        expected: format!("{:?}", data_type),
+        found: format!("{:?}", value_type)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use polars::prelude::{ DataFrame, Series, NamedFrom };
+
+    use bionet_common::{
+        neuron::NeuronID,
+        data::{ DataType, DataTypeValue, DataCategory }
+    };
+
+    use crate::simple::fuzzy::{ FuzzyActivation, FuzzyKernel };
+
+    use super::{ MagdsBuilder, SensorOptions, MissingPolicy, BuildError };
+
+    #[test]
+    fn csv() {
+        let magds = MagdsBuilder::new("iris").build_from_csv("data/iris.csv").unwrap();
+        assert_eq!(magds.neurons.len(), 150);
+        let setosa = magds.sensor_search("variety".into(), &"Setosa".to_string().into());
+        assert_eq!(setosa.unwrap().borrow().counter(), 49);
+        let record = magds.record(&NeuronID::new("1", "iris")).unwrap();
+        assert!(record.value("sepal.length").is_none());
+
+        let magds = MagdsBuilder::new("iris")
+            .sensor("petal.width", SensorOptions {
+                data_category: Some(DataCategory::Ordinal), ..SensorOptions::default()
+            })
+            .sensor("sepal.length", SensorOptions {
+                fuzzy: Some(FuzzyActivation::new(FuzzyKernel::Gaussian, 0.2)),
+                missing: Some(MissingPolicy::Fill(5.0_f64.into())),
+                ..SensorOptions::default()
+            })
+            .sensor("variety", SensorOptions::default())
+            .only_declared_sensors(true)
+            .collapse_duplicates(true)
+            .build_from_csv("data/iris.csv")
+            .unwrap();
+        assert_eq!(magds.sensors.len(), 3);
+        // rows are compared on the three declared sensors only, 150 rows hold 115 distinct
+        // (sepal.length, petal.width, variety) triples, the full rows would give 149
+        assert_eq!(magds.neurons.len(), 115);
+        let counters: Vec<usize> = magds.neurons.values().map(|n| n.borrow().counter()).collect();
+        assert_eq!(counters.iter().sum::<usize>(), 150);
+        assert!(counters.iter().any(|counter| *counter > 1));
+        let setosa = magds.sensor_search("variety".into(), &"Setosa".to_string().into());
+        let setosa_records = magds.neurons.values()
+            .filter(|n| n.borrow().explain().keys().any(|id| &*id.id == "Setosa"))
+            .count();
+        assert_eq!(setosa.unwrap().borrow().counter(), setosa_records);
+        assert!(matches!(
+            magds.sensor_data_category("petal.width".into()), Some(DataCategory::Ordinal)
+        ));
+        assert!(magds.fuzzy_activation("sepal.length".into()).is_some());
+        let record = magds.record(&NeuronID::new("1", "iris")).unwrap();
+        assert_eq!(record.value("sepal.length").unwrap().as_f64(), Some(&5.0));
+    }
+
+    #[test]
+    fn build_errors() {
+        let builder = MagdsBuilder::new("iris").missing(MissingPolicy::Reject);
+        assert_eq!(
+            builder.build_from_csv("data/iris.csv").err(),
+            Some(BuildError::MissingValue { sensor: "sepal.length".into(), row: 0 })
+        );
+
+        let builder = MagdsBuilder::new("iris").sensor("unknown", SensorOptions::default());
+        assert_eq!(
+            builder.build_from_csv("data/iris.csv").err(),
+            Some(BuildError::MissingColumn("unknown".into()))
+        );
+
+        let builder = MagdsBuilder::new("iris").sensor("variety", SensorOptions {
+            missing: Some(MissingPolicy::Fill(1.0_f64.into())), ..SensorOptions::default()
+        });
+        let rows: Vec<Vec<(Rc<str>, Option<DataTypeValue>)>> = vec![
+            vec![("variety".into(), Some("Setosa".to_string().into()))],
+            vec![("variety".into(), None)]
+        ];
+        assert!(matches!(builder.build_from_rows(rows), Err(BuildError::TypeMismatch { .. })));
+
+        assert!(matches!(
+            MagdsBuilder::new("iris").build_from_csv("data/missing.csv"), Err(BuildError::Csv(_))
+        ));
+        let tags = Series::new("tags", vec!["[a, b]", "[a, , b]"]);
+        let df = DataFrame::new(vec![tags]).unwrap();
+        assert!(matches!(
+            MagdsBuilder::new("lists").build_from_df(&df),
+            Err(BuildError::InvalidList { row: 1, .. })
+        ));
+        let df = DataFrame::new(vec![Series::new("tags", vec!["[a, , b]"])]).unwrap();
+        assert!(MagdsBuilder::new("lists").parse_lists(false).build_from_df(&df).is_ok());
+        let df = DataFrame::new(vec![Series::new("tags", vec!["[a, b]", "[]"])]).unwrap();
+        let magds = MagdsBuilder::new("lists").build_from_df(&df).unwrap();
+        assert_eq!(magds.neurons.len(), 2);
+        assert!(magds.record(&NeuronID::new("2", "lists")).unwrap().get("tags").is_none());
+
+        let empty: Vec<Vec<(Rc<str>, Option<DataTypeValue>)>> = Vec::new();
+        let result = MagdsBuilder::new("empty").build_from_rows(empty);
+        assert_eq!(result.err(), Some(BuildError::NoSensors));
+    }
+
+    #[test]
+    fn rows() {
+        let rows: Vec<Vec<(Rc<str>, Option<DataTypeValue>)>> = vec![
+            vec![
+                ("title".into(), Some("graphs".to_string().into())),
+                ("tags".into(), Some("a".to_string().into())),
+                ("tags".into(), Some("b".to_string().into()))
+            ],
+            vec![
+                ("title".into(), Some("notes".to_string().into())),
+                ("pages".into(), Some(3_u32.into()))
+            ]
+        ];
+        let magds = MagdsBuilder::new("documents")
+            .sensor("pages", SensorOptions {
+                data_type: Some(DataType::U32), ..SensorOptions::default()
+            })
+            .build_from_rows(rows)
+            .unwrap();
+
+        assert_eq!(magds.neurons.len(), 2);
+        let graphs = magds.record(&NeuronID::new("1", "documents")).unwrap();
+        assert_eq!(graphs.get("tags").unwrap().to_vec().len(), 2);
+        assert!(graphs.value("pages").is_none());
+        let notes = magds.record(&NeuronID::new("2", "documents")).unwrap();
+        assert_eq!(notes.value("pages").unwrap().as_u32(), Some(&3));
+        assert!(notes.get("tags").is_none());
+    }
+}
@@ -0,0 +1,268 @@
+use std::{
+    collections::HashMap,
+    rc::Rc,
+    cell::RefCell,
+    ops::Bound
+};
+
+use ordered_float::OrderedFloat;
+
+use bionet_common::{
+    neuron::{ Neuron, NeuronID },
+    data::{ DataTypeValue, DataCategory }
+};
+
+use super::{
+    magds::MAGDS,
+    sensor::SensorElement,
+    inhibition::Inhibition,
+    fuzzy
+};
+
+pub struct QueryContext<'a> {
+    magds: &'a MAGDS,
+    elements: HashMap<NeuronID, f32>,
+    neurons: HashMap<NeuronID, f32>
+}
+
+impl<'a> QueryContext<'a> {
+    pub fn new(magds: &'a MAGDS) -> QueryContext<'a> {
+        QueryContext { magds, elements: HashMap::new(), neurons: HashMap::new() }
+    }
+
+    pub fn activate(
+        &mut self, id: Rc<str>, item: &DataTypeValue, signal: f32, fuzzy: bool
+    ) -> Result<(), String> {
+        let data_category = self.magds.sensor_data_category(id.clone())
+            .ok_or(format!("sensor {id} doesn't exists"))?;
+        let numerical = matches!(data_category, DataCategory::Numerical | DataCategory::Ordinal);
+
+        let weights: Vec<(SensorElement, f32)> = match fuzzy::numeric_value(item) {
+            Some(value) if fuzzy && numerical => {
+                match self.magds.kernel_weights(&id, value) {
+                    Some(weights) => weights,
+                    None => self.propagated_weights(&id, item)?
+                }
+            }
+            _ => {
+                let sensor = self.magds.sensor(id.clone())
+                    .ok_or(format!("sensor {id} doesn't exists"))?;
+                let elements = sensor.borrow()
+                    .range(Bound::Included(item), Bound::Included(item))?;
+                if elements.is_empty() {
+                    return Err(format!("value {:?} doesn't exists in sensor {id}", item))
+                }
+                elements.into_iter().map(|element| (element, 1.0f32)).collect()
+            }
+        };
+
+        for ((element, defined_neurons), weight) in weights {
+            let element = element.borrow();
+            let element_signal = signal * weight;
+            *self.elements.entry(element.id()).or_insert(0.0f32) += element_signal;
+
+            let neuron_signal = element_signal / element.counter() as f32;
+            let mut neuron_ids: Vec<NeuronID> = defined_neurons.into_keys().collect();
+            neuron_ids.sort_by(|a, b| a.id.cmp(&b.id));
+            for neuron_id in neuron_ids { self.activate_neuron(&neuron_id, neuron_signal); }
+        }
+        Ok(())
+    }
+
+    fn activate_neuron(&mut self, id: &NeuronID, signal: f32) {
+        let neuron = match self.magds.neuron_from_id(id) {
+            Some(neuron) => neuron,
+            None => return
+        };
+        let neuron = neuron.borrow();
+        let activation = neuron.accumulate(self.neuron_activation(id), signal);
+        self.neurons.insert(id.clone(), activation);
+
+        let defined_neurons = neuron.defined_neurons();
+        let output_signal = neuron.output(activation, defined_neurons.len());
+        if output_signal == 0.0f32 { return }
+        for (defined_id, defined_neuron) in defined_neurons {
+            if defined_neuron.borrow().is_sensor() { continue }
+            self.activate_neuron(&defined_id, output_signal);
+        }
+    }
+
+    pub fn inhibit(&mut self, inhibition: Inhibition) {
+        let activations: Vec<(NeuronID, f32)> = self.neurons.drain().collect();
+        self.neurons = inhibition.inhibit_groups(activations).into_iter()
+            .filter(|(_id, activation)| *activation > 0.0f32)
+            .collect();
+    }
+
+    fn propagated_weights(
+        &self, id: &Rc<str>, item: &DataTypeValue
+    ) -> Result<Vec<(SensorElement, f32)>, String> {
+        let sensor = self.magds.sensor(id.clone()).ok_or(format!("sensor {id} doesn't exists"))?;
+        let stimuli = self.magds.nearest_stimuli(id, item).unwrap_or_default();
+
+        let mut weights: Vec<(SensorElement, f32)> = Vec::new();
+        for (key, _element, stimulus_weight) in stimuli {
+            for (propagated, weight) in sensor.borrow().propagation_weights(&key)? {
+                weights.push((propagated, stimulus_weight * weight));
+            }
+        }
+        Ok(weights)
+    }
+
+    pub fn element_activation(&self, id: &NeuronID) -> f32 {
+        self.elements.get(id).copied().unwrap_or(0.0f32)
+    }
+
+    pub fn neuron_activation(&self, id: &NeuronID) -> f32 {
+        self.neurons.get(id).copied().unwrap_or(0.0f32)
+    }
+
+    pub fn neurons(&self) -> Vec<(NeuronID, f32)> {
+        let mut neurons: Vec<(NeuronID, f32)> = self.neurons.iter()
+            .map(|(id, activation)| (id.clone(), *activation))
+            .collect();
+        neurons.sort_by(|a, b| {
+            (OrderedFloat(b.1), &a.0.id).cmp(&(OrderedFloat(a.1), &b.0.id))
+        });
+        neurons
+    }
+
+    pub fn winner(&self) -> Option<(NeuronID, f32)> { self.neurons().into_iter().next() }
+
+    pub fn predict(
+        &mut self, features: &[(Rc<str>, DataTypeValue)], target: Rc<str>, fuzzy: bool
+    ) -> Option<(DataTypeValue, f64)> {
+        for (id, value) in features {
+            let weight = self.magds.feature_weight(id.clone()).unwrap_or(1.0f32);
+            if weight == 0.0f32 {
+                log::info!("feature {id} has zero weight, skipping");
+                continue
+            }
+            if let Err(e) = self.activate(id.clone(), value, weight, fuzzy) {
+                log::warn!("cannot activate sensor {id} value {:?}, error: {e}", value);
+            }
+        }
+
+        if let Some(inhibition) = self.magds.inhibition() { self.inhibit(inhibition); }
+
+        let (winner_id, activation) = self.winner()?;
+        let winner = self.magds.neuron_from_id(&winner_id)? as Rc<RefCell<dyn Neuron>>;
+        let value = winner.borrow().explain_one(target)?;
+        Some((value, activation as f64 / features.len() as f64))
+    }
+
+    pub fn reset(&mut self) {
+        self.elements.clear();
+        self.neurons.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        rc::Rc
+    };
+
+    use bionet_common::{
+        neuron::{ NeuronID, NeuronConnectBilateral },
+        connection::ConnectionKind,
+        data::DataTypeValue
+    };
+
+    use crate::{
+        simple::{ parser, inhibition::Inhibition },
+        neuron::activation::{ ActivationPolicy, NormalizedActivation, SaturatingActivation },
+        algorithm::predict
+    };
+
+    use super::QueryContext;
+
+    #[test]
+    fn isolated_contexts() {
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let neuron_15 = NeuronID::new("15", "iris");
+        let neuron_16 = NeuronID::new("16", "iris");
+
+        let mut first = QueryContext::new(&magds);
+        let mut second = QueryContext::new(&magds);
+        first.activate("sepal.length".into(), &5.8_f64.into(), 1.0, false).unwrap();
+        second.activate("variety".into(), &"Setosa".to_string().into(), 1.0, false).unwrap();
+
+        assert_eq!(first.neuron_activation(&neuron_15), 1.0_f32 / 7_f32);
+        assert_eq!(first.neuron_activation(&neuron_16), 0.0_f32);
+        assert_eq!(second.neuron_activation(&neuron_15), 0.0_f32);
+        assert_eq!(second.neurons().len(), 49);
+        assert_eq!(magds.neuron("15", "iris").unwrap().borrow().activation(), 0.0_f32);
+
+        first.activate("sepal.length".into(), &5.8_f64.into(), 1.0, true).unwrap();
+        let sl57 = first.element_activation(&NeuronID::new("5.7", "sepal.length"));
+        assert!((sl57 - 0.9722222_f32).abs() < 1e-5);
+        assert!(first.neuron_activation(&neuron_16) > 0.0_f32);
+        assert!(first.activate("unknown".into(), &1.0_f64.into(), 1.0, false).is_err());
+
+        first.reset();
+        assert!(first.neurons().is_empty());
+
+        let features: Vec<(Rc<str>, DataTypeValue)> = vec![
+            ("sepal.length".into(), 5.8_f64.into()),
+            ("sepal.width".into(), 2.7_f64.into()),
+            ("petal.length".into(), 4.1_f64.into()),
+            ("petal.width".into(), 1.0_f64.into())
+        ];
+        let (value, proba) = first.predict(&features, "variety".into(), false).unwrap();
+        let (expected, expected_proba) = predict::predict(
+            &mut magds, &features, "variety".into(), false
+        ).unwrap();
+        assert_eq!(value.as_string(), expected.as_string());
+        assert!((proba - expected_proba).abs() < 1e-6);
+    }
+
+    #[test]
+    fn graph_rules() {
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let group = magds.create_neuron(NeuronID::new("setosa", "groups")).unwrap();
+        let neuron_15 = magds.neuron("15", "iris").unwrap();
+        neuron_15.borrow_mut()
+            .connect_bilateral_to(group.clone(), ConnectionKind::Defining)
+            .unwrap();
+
+        let features: Vec<(Rc<str>, DataTypeValue)> = vec![
+            ("sepal.length".into(), 5.85_f64.into()),
+            ("sepal.width".into(), 4.0_f64.into()),
+            ("petal.length".into(), 1.2_f64.into()),
+            ("petal.width".into(), 0.2_f64.into())
+        ];
+        let weighted: Vec<(Rc<str>, DataTypeValue, f32)> = features.iter()
+            .map(|(id, value)| (id.clone(), value.clone(), 1.0f32))
+            .collect();
+
+        let policies: Vec<Rc<dyn ActivationPolicy>> = vec![
+            Rc::new(NormalizedActivation),
+            Rc::new(SaturatingActivation { threshold: 0.0, saturation: 0.1 })
+        ];
+        for policy in policies {
+            magds.set_activation_policy(policy);
+            for inhibition in [None, Some(Inhibition::WinnerTakeAll(3))] {
+                magds.set_inhibition(inhibition);
+
+                let mut context = QueryContext::new(&magds);
+                context.predict(&features, "variety".into(), true);
+                let in_context: HashMap<NeuronID, f32> = context.neurons().into_iter()
+                    .filter(|(_id, activation)| *activation > 0.0f32)
+                    .collect();
+                assert!(in_context.contains_key(&NeuronID::new("setosa", "groups")));
+
+                predict::activate_features(&mut magds, &weighted, true);
+                let in_graph: HashMap<NeuronID, f32> = magds.active_neurons().into_iter()
+                    .collect();
+                magds.deactivate();
+
+                assert_eq!(in_context.len(), in_graph.len());
+                for (id, activation) in &in_graph {
+                    assert!((in_context[id] - activation).abs() < 1e-5, "{:?}", id);
+                }
+            }
+        }
+    }
+}
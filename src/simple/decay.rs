@@ -0,0 +1,14 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ActivationDecay {
+    PerStep(f32),
+    PerSecond(f32)
+}
+
+impl ActivationDecay {
+    pub fn factor(&self, elapsed_seconds: f32) -> f32 {
+        match self {
+            ActivationDecay::PerStep(factor) => *factor,
+            ActivationDecay::PerSecond(factor) => factor.powf(elapsed_seconds)
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use bionet_common::data::{ DataType, DataTypeValue };
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FuzzyKernel {
+    Linear,
+    Gaussian,
+    Step
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FuzzyActivation {
+    pub kernel: FuzzyKernel,
+    pub radius: f64,
+    pub min_activation: f32
+}
+
+impl FuzzyActivation {
+    pub fn new(kernel: FuzzyKernel, radius: f64) -> FuzzyActivation {
+        FuzzyActivation { kernel, radius, min_activation: 0.0f32 }
+    }
+
+    pub fn weight(&self, distance: f64) -> f32 {
+        let distance = distance.abs();
+        let weight = if self.radius <= 0.0 {
+            if distance == 0.0 { 1.0 } else { 0.0 }
+        } else {
+            match self.kernel {
+                FuzzyKernel::Linear => (1.0 - distance / self.radius).max(0.0),
+                FuzzyKernel::Gaussian => (-distance.powi(2) / (2.0 * self.radius.powi(2))).exp(),
+                FuzzyKernel::Step => if distance <= self.radius { 1.0 } else { 0.0 }
+            }
+        } as f32;
+
+        if weight < self.min_activation { 0.0f32 } else { weight }
+    }
+
+    pub fn support(&self) -> f64 {
+        if self.radius <= 0.0 { return 0.0 }
+        match self.kernel {
+            FuzzyKernel::Linear => self.radius * (1.0 - self.min_activation.max(0.0) as f64),
+            FuzzyKernel::Gaussian => {
+                if self.min_activation <= 0.0 { return f64::INFINITY }
+                if self.min_activation > 1.0 { return 0.0 }
+                self.radius * (-2.0 * (self.min_activation as f64).ln()).sqrt()
+            }
+            FuzzyKernel::Step => self.radius
+        }
+    }
+}
+
+pub(crate) fn numeric_value(value: &DataTypeValue) -> Option<f64> {
+    match value {
+        DataTypeValue::U8(v) => Some(*v as f64),
+        DataTypeValue::U16(v) => Some(*v as f64),
+        DataTypeValue::U32(v) => Some(*v as f64),
+        DataTypeValue::U64(v) => Some(*v as f64),
+        DataTypeValue::U128(v) => Some(*v as f64),
+        DataTypeValue::USize(v) => Some(*v as f64),
+        DataTypeValue::I8(v) => Some(*v as f64),
+        DataTypeValue::I16(v) => Some(*v as f64),
+        DataTypeValue::I32(v) => Some(*v as f64),
+        DataTypeValue::I64(v) => Some(*v as f64),
+        DataTypeValue::I128(v) => Some(*v as f64),
+        DataTypeValue::ISize(v) => Some(*v as f64),
+        DataTypeValue::F32(v) => Some(*v as f64),
+        DataTypeValue::F64(v) => Some(*v),
+        _ => None
+    }
+}
+
+pub(crate) fn typed_value(value: f64, data_type: &DataType) -> Option<DataTypeValue> {
+    match data_type {
+        DataType::U8 => Some((value as u8).into()),
+        DataType::U16 => Some((value as u16).into()),
+        DataType::U32 => Some((value as u32).into()),
+        DataType::U64 => Some((value as u64).into()),
+        DataType::U128 => Some((value as u128).into()),
+        DataType::USize => Some((value as usize).into()),
+        DataType::I8 => Some((value as i8).into()),
+        DataType::I16 => Some((value as i16).into()),
+        DataType::I32 => Some((value as i32).into()),
+        DataType::I64 => Some((value as i64).into()),
+        DataType::I128 => Some((value as i128).into()),
+        DataType::ISize => Some((value as isize).into()),
+        DataType::F32 => Some((value as f32).into()),
+        DataType::F64 => Some(value.into()),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ FuzzyActivation, FuzzyKernel };
+
+    #[test]
+    fn kernels() {
+        let linear = FuzzyActivation::new(FuzzyKernel::Linear, 2.0);
+        assert_eq!(linear.weight(0.0), 1.0);
+        assert_eq!(linear.weight(-1.0), 0.5);
+        assert_eq!(linear.weight(3.0), 0.0);
+
+        let gaussian = FuzzyActivation::new(FuzzyKernel::Gaussian, 1.0);
+        assert_eq!(gaussian.weight(0.0), 1.0);
+        assert!(gaussian.weight(1.0) > gaussian.weight(2.0));
+        assert!(gaussian.weight(5.0) > 0.0);
+
+        let step = FuzzyActivation {
+            min_activation: 0.5, ..FuzzyActivation::new(FuzzyKernel::Step, 1.0)
+        };
+        assert_eq!(step.weight(1.0), 1.0);
+        assert_eq!(step.weight(1.5), 0.0);
+
+        let cut = FuzzyActivation { min_activation: 0.6, ..linear };
+        assert_eq!(cut.weight(1.0), 0.0);
+    }
+
+    #[test]
+    fn support() {
+        let linear = FuzzyActivation::new(FuzzyKernel::Linear, 2.0);
+        assert_eq!(linear.support(), 2.0);
+        assert_eq!(FuzzyActivation { min_activation: 0.5, ..linear }.support(), 1.0);
+
+        let gaussian = FuzzyActivation::new(FuzzyKernel::Gaussian, 1.0);
+        assert_eq!(gaussian.support(), f64::INFINITY);
+        let gaussian = FuzzyActivation { min_activation: 0.1, ..gaussian };
+        let support = gaussian.support();
+        assert!((gaussian.weight(support) - 0.1).abs() < 1e-6);
+        assert_eq!(gaussian.weight(support + 0.01), 0.0);
+
+        assert_eq!(FuzzyActivation::new(FuzzyKernel::Step, 1.5).support(), 1.5);
+    }
+}
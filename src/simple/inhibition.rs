@@ -0,0 +1,80 @@
+use std::{
+    collections::HashMap,
+    rc::Rc
+};
+
+use ordered_float::OrderedFloat;
+
+use bionet_common::neuron::NeuronID;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Inhibition {
+    Threshold(f32),
+    WinnerTakeAll(usize),
+    Lateral(f32)
+}
+
+impl Inhibition {
+    pub(crate) fn inhibit_groups(&self, activations: Vec<(NeuronID, f32)>) -> Vec<(NeuronID, f32)> {
+        let mut groups: HashMap<Rc<str>, Vec<(NeuronID, f32)>> = HashMap::new();
+        for (id, activation) in activations {
+            if activation == 0.0f32 { continue }
+            groups.entry(id.parent_id.clone()).or_default().push((id, activation));
+        }
+
+        let mut inhibited: Vec<(NeuronID, f32)> = Vec::new();
+        for (_parent_id, mut neurons) in groups {
+            neurons.sort_by(|a, b| a.0.id.cmp(&b.0.id));
+            let activations: Vec<f32> = neurons.iter()
+                .map(|(_id, activation)| *activation)
+                .collect();
+            let ids = neurons.into_iter().map(|(id, _activation)| id);
+            inhibited.extend(ids.zip(self.inhibit(&activations)));
+        }
+        inhibited
+    }
+
+    pub(crate) fn inhibit(&self, activations: &[f32]) -> Vec<f32> {
+        let max = activations.iter().cloned().fold(0.0f32, f32::max);
+        if max <= 0.0f32 { return activations.to_vec() }
+
+        match self {
+            Inhibition::Threshold(fraction) => {
+                activations.iter()
+                    .map(|a| if *a < fraction * max { 0.0f32 } else { *a })
+                    .collect()
+            }
+            Inhibition::WinnerTakeAll(k) => {
+                let mut order: Vec<usize> = (0..activations.len()).collect();
+                order.sort_by_key(|i| OrderedFloat(-activations[*i]));
+                let mut inhibited = vec![0.0f32; activations.len()];
+                for i in order.into_iter().take(*k) { inhibited[i] = activations[i]; }
+                inhibited
+            }
+            Inhibition::Lateral(strength) => {
+                activations.iter()
+                    .map(|a| (a - strength * (max - a)).max(0.0f32))
+                    .collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Inhibition;
+
+    #[test]
+    fn inhibit() {
+        let activations = [1.0, 0.8, 0.4, 0.0];
+        assert_eq!(Inhibition::Threshold(0.5).inhibit(&activations), vec![1.0, 0.8, 0.0, 0.0]);
+        assert_eq!(Inhibition::WinnerTakeAll(1).inhibit(&activations), vec![1.0, 0.0, 0.0, 0.0]);
+        assert_eq!(Inhibition::WinnerTakeAll(2).inhibit(&activations), vec![1.0, 0.8, 0.0, 0.0]);
+
+        let lateral = Inhibition::Lateral(1.0).inhibit(&activations);
+        assert_eq!(lateral[0], 1.0);
+        assert!((lateral[1] - 0.6).abs() < 1e-6);
+        assert_eq!(lateral[2], 0.0);
+        assert_eq!(lateral[3], 0.0);
+    }
+}
@@ -1,33 +1,108 @@
 use std::{
     rc::Rc,
-    cell::RefCell,
-    collections::HashMap,
-    fmt::{ Display, Formatter, Result as FmtResult }
+    cell::{ RefCell, Ref },
+    collections::{ HashMap, HashSet },
+    fmt::{ Display, Formatter, Result as FmtResult },
+    ops::Bound,
+    time::Instant
 };
 
+use ordered_float::OrderedFloat;
+use polars::prelude::DataFrame;
+
 use asa_graphs::neural::graph::ASAGraph;
 use bionet_common::{
     neuron::{ Neuron, NeuronID },
-    data::{ DataType, DataTypeValue, DataCategory },
+    data::{ DataType, DataTypeValue, DataTypeValueStr, DataCategory },
     sensor::Sensor
 };
 
-use crate::neuron::simple_neuron::SimpleNeuron;
+use crate::{
+    neuron::{
+        simple_neuron::SimpleNeuron,
+        activation::{ ActivationPolicy, DefaultActivation }
+    },
+    algorithm::association::{ self, Correlation }
+};
 
-use super::sensor::SensorConatiner;
+use super::{
+    sensor::{ SensorConatiner, SensorElement },
+    fuzzy::{ self, FuzzyActivation },
+    trace::{ ActivationTrace, ActivationEvent, ActivationDirection },
+    decay::ActivationDecay,
+    inhibition::Inhibition,
+    record::Record
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimilarNeuron {
+    pub neuron: NeuronID,
+    pub similarity: f32,
+    pub shared: Vec<NeuronID>
+}
+
+#[derive(Clone)]
+pub struct RangeQueryResult {
+    pub elements: Vec<Rc<RefCell<dyn Neuron>>>,
+    pub neurons: HashMap<NeuronID, Rc<RefCell<SimpleNeuron>>>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpreadingOptions {
+    pub hops: usize,
+    pub attenuation: f32,
+    pub fuzzy: bool
+}
+
+impl Default for SpreadingOptions {
+    fn default() -> Self { SpreadingOptions { hops: 1, attenuation: 0.5, fuzzy: false } }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpreadingActivation {
+    pub elements: HashMap<Rc<str>, Vec<(NeuronID, f32)>>,
+    pub neurons: Vec<(NeuronID, f32)>
+}
 
 pub struct MAGDS {
     pub(crate) sensors: HashMap<Rc<str>, Rc<RefCell<SensorConatiner>>>,
-    pub(crate) neurons: HashMap<NeuronID, Rc<RefCell<SimpleNeuron>>>
+    pub(crate) neurons: HashMap<NeuronID, Rc<RefCell<SimpleNeuron>>>,
+    pub(crate) neurons_order: Vec<NeuronID>,
+    pub(crate) feature_weights: HashMap<Rc<str>, f32>,
+    pub(crate) activation_policy: Rc<dyn ActivationPolicy>,
+    pub(crate) fuzzy_activations: HashMap<Rc<str>, FuzzyActivation>,
+    pub(crate) trace: Option<Rc<RefCell<ActivationTrace>>>,
+    pub(crate) activation_decay: Option<ActivationDecay>,
+    pub(crate) last_activation: Option<Instant>,
+    pub(crate) inhibition: Option<Inhibition>,
+    pub(crate) data_categories: HashMap<Rc<str>, DataCategory>,
+    pub(crate) activated_elements: HashMap<NeuronID, Rc<RefCell<dyn Neuron>>>,
+    pub(crate) activated_sensors: HashSet<Rc<str>>,
+    pub(crate) activated_neurons: HashSet<NeuronID>
 }
 
 impl MAGDS {
     pub fn new() -> MAGDS {
-        MAGDS { sensors: HashMap::new(), neurons: HashMap::new() }
+        MAGDS { 
+            sensors: HashMap::new(), 
+            neurons: HashMap::new(),
+            neurons_order: Vec::new(),
+            feature_weights: HashMap::new(),
+            activation_policy: Rc::new(DefaultActivation),
+            fuzzy_activations: HashMap::new(),
+            trace: None,
+            activation_decay: None,
+            last_activation: None,
+            inhibition: None,
+            data_categories: HashMap::new(),
+            activated_elements: HashMap::new(),
+            activated_sensors: HashSet::new(),
+            activated_neurons: HashSet::new()
+        }
     }
     
     pub fn new_rc() -> Rc<RefCell<MAGDS>> {
-        Rc::new(RefCell::new(MAGDS { sensors: HashMap::new(), neurons: HashMap::new() }))
+        Rc::new(RefCell::new(MAGDS::new()))
     }
 
     pub fn create_sensor(
@@ -81,7 +156,19 @@ impl MAGDS {
     }
 
     pub fn sensor_data_category(&self, id: Rc<str>) -> Option<DataCategory> { 
-        Some(self.sensors.get(&id)?.borrow().data_category())
+        let sensor = self.sensors.get(&id)?;
+        match self.data_categories.get(&id) {
+            Some(data_category) => Some(data_category.clone()),
+            None => Some(sensor.borrow().data_category())
+        }
+    }
+
+    pub fn set_sensor_data_category(
+        &mut self, id: Rc<str>, data_category: DataCategory
+    ) -> Result<(), String> {
+        if !self.sensors.contains_key(&id) { return Err(format!("sensor {id} doesn't exists")) }
+        self.data_categories.insert(id, data_category);
+        Ok(())
     }
 
     pub fn sensor_insert(
@@ -90,12 +177,67 @@ impl MAGDS {
         Some(self.sensors.get_mut(&id)?.borrow_mut().insert(item))
     }
     
+    pub fn sensor_insert_connected(
+        &mut self, id: Rc<str>, item: &DataTypeValue, neuron: &Rc<RefCell<SimpleNeuron>>
+    ) -> Result<Rc<RefCell<dyn Neuron>>, String> {
+        self.sensors
+            .get_mut(&id)
+            .unwrap_or(Err(format!("sensor {} doesn't exists", id))?)
+            .borrow_mut()
+            .insert_connected(item, neuron)
+    }
+    
     pub fn sensor_search(
         &self, id: Rc<str>, item: &DataTypeValue
     ) -> Option<Rc<RefCell<dyn Neuron>>> { 
         self.sensors.get(&id)?.borrow().search(item) 
     }
 
+    pub fn range_query(
+        &self, id: Rc<str>, lower: &DataTypeValue, upper: &DataTypeValue, inclusive: bool
+    ) -> Result<RangeQueryResult, String> {
+        if inclusive {
+            self.range_query_bounds(id, Bound::Included(lower), Bound::Included(upper))
+        } else {
+            self.range_query_bounds(id, Bound::Excluded(lower), Bound::Excluded(upper))
+        }
+    }
+
+    pub fn greater_than(
+        &self, id: Rc<str>, lower: &DataTypeValue, inclusive: bool
+    ) -> Result<RangeQueryResult, String> {
+        let lower = if inclusive { Bound::Included(lower) } else { Bound::Excluded(lower) };
+        self.range_query_bounds(id, lower, Bound::Unbounded)
+    }
+
+    pub fn less_than(
+        &self, id: Rc<str>, upper: &DataTypeValue, inclusive: bool
+    ) -> Result<RangeQueryResult, String> {
+        let upper = if inclusive { Bound::Included(upper) } else { Bound::Excluded(upper) };
+        self.range_query_bounds(id, Bound::Unbounded, upper)
+    }
+
+    pub fn range_query_bounds(
+        &self, id: Rc<str>, lower: Bound<&DataTypeValue>, upper: Bound<&DataTypeValue>
+    ) -> Result<RangeQueryResult, String> {
+        let elements = self.sensors
+            .get(&id)
+            .ok_or(format!("sensor {} doesn't exists", id))?
+            .borrow()
+            .range(lower, upper)?;
+
+        let mut result = RangeQueryResult { elements: Vec::new(), neurons: HashMap::new() };
+        for (element, defined_neurons) in elements {
+            for neuron_id in defined_neurons.keys() {
+                if let Some(neuron) = self.neurons.get(neuron_id) {
+                    result.neurons.insert(neuron_id.clone(), neuron.clone());
+                }
+            }
+            result.elements.push(element);
+        }
+        Ok(result)
+    }
+
     pub fn sensor_activate(
         &mut self, 
         id: Rc<str>, 
@@ -104,11 +246,133 @@ impl MAGDS {
         propagate_horizontal: bool, 
         propagate_vertical: bool
     ) -> Result<HashMap<NeuronID, Rc<RefCell<dyn Neuron>>>, String> {
-        self.sensors
-            .get_mut(&id)
-            .unwrap_or(Err(format!("sensor {} doesn't exists", id))?)
-            .borrow_mut()
-            .activate(item, signal, propagate_horizontal, propagate_vertical)
+        self.auto_decay();
+        self.sensor_stimulate(id, item, signal, propagate_horizontal, propagate_vertical)
+    }
+
+    pub(crate) fn sensor_stimulate(
+        &mut self, 
+        id: Rc<str>, 
+        item: &DataTypeValue,
+        signal: f32,
+        propagate_horizontal: bool, 
+        propagate_vertical: bool
+    ) -> Result<HashMap<NeuronID, Rc<RefCell<dyn Neuron>>>, String> {
+        let element = self.sensors
+            .get(&id)
+            .ok_or(format!("sensor {} doesn't exists", id))?
+            .borrow()
+            .search(item)
+            .ok_or(format!("value {:?} doesn't exists in sensor {id}", item))?;
+        let element_id = element.borrow().id();
+
+        self.next_trace_step();
+        let neurons = self.activate_element(
+            &element_id, 
+            &element, 
+            signal, 
+            propagate_horizontal, 
+            propagate_vertical, 
+            ActivationDirection::Stimulus
+        );
+
+        self.activated_elements.insert(element_id, element);
+        if propagate_horizontal { self.activated_sensors.insert(id); }
+        self.track_neurons(&neurons);
+        Ok(neurons)
+    }
+
+    fn track_neurons(&mut self, neurons: &HashMap<NeuronID, Rc<RefCell<dyn Neuron>>>) {
+        self.activated_neurons.extend(
+            neurons.keys().filter(|id| self.neurons.contains_key(*id)).cloned()
+        );
+    }
+
+    fn next_trace_step(&self) {
+        if let Some(trace) = &self.trace { trace.borrow_mut().next_step(); }
+    }
+
+    fn activate_element(
+        &mut self,
+        source: &NeuronID,
+        element: &Rc<RefCell<dyn Neuron>>,
+        signal: f32,
+        propagate_horizontal: bool,
+        propagate_vertical: bool,
+        direction: ActivationDirection
+    ) -> HashMap<NeuronID, Rc<RefCell<dyn Neuron>>> {
+        let trace = match self.trace.clone() {
+            Some(trace) => trace,
+            None => {
+                return element.borrow_mut()
+                    .activate(signal, propagate_horizontal, propagate_vertical)
+            }
+        };
+
+        let element_id = element.borrow().id();
+        let (event_index, previous_source) = {
+            let mut trace = trace.borrow_mut();
+            (trace.events.len(), trace.source.replace(element_id.clone()))
+        };
+        let mut neurons = element.borrow_mut().activate(signal, false, propagate_vertical);
+        {
+            let mut trace = trace.borrow_mut();
+            trace.source = previous_source;
+            let step = trace.steps();
+            trace.events.insert(event_index, ActivationEvent {
+                step,
+                sensor: element_id.parent_id.clone(),
+                source: source.clone(),
+                target: element_id.clone(),
+                signal,
+                activation: element.borrow().activation(),
+                direction
+            });
+        }
+
+        if propagate_horizontal {
+            for ((neighbour, _defined_neurons), weight) in self.propagation_weights(&element_id) {
+                neurons.extend(self.activate_element(
+                    &element_id, 
+                    &neighbour, 
+                    signal * weight, 
+                    false, 
+                    propagate_vertical, 
+                    ActivationDirection::Horizontal
+                ));
+            }
+        }
+        neurons
+    }
+
+    fn propagation_weights(&self, id: &NeuronID) -> Vec<(SensorElement, f32)> {
+        let sensor = match self.sensors.get(&id.parent_id) {
+            Some(sensor) => sensor.borrow(),
+            None => return Vec::new()
+        };
+        let key = match DataTypeValueStr(&id.id).data_type_value(sensor.data_type()) {
+            Some(key) => key,
+            None => return Vec::new()
+        };
+        sensor.propagation_weights(&key).unwrap_or_default().into_iter().skip(1).collect()
+    }
+
+    pub fn start_trace(&mut self) {
+        let trace = Rc::new(RefCell::new(ActivationTrace::new()));
+        for neuron in self.neurons.values() { neuron.borrow_mut().trace = Some(trace.clone()); }
+        self.trace = Some(trace);
+    }
+
+    pub fn stop_trace(&mut self) -> Option<ActivationTrace> {
+        let trace = self.trace.take()?;
+        for neuron in self.neurons.values() { neuron.borrow_mut().trace = None; }
+        Some(Rc::try_unwrap(trace).map(RefCell::into_inner).unwrap_or_else(|trace| {
+            trace.borrow().clone()
+        }))
+    }
+
+    pub fn trace(&self) -> Option<Ref<ActivationTrace>> {
+        self.trace.as_ref().map(|trace| trace.borrow())
     }
 
     pub fn sensor_deactivate(
@@ -138,11 +402,14 @@ impl MAGDS {
         &mut self, id: NeuronID
     ) -> Option<Rc<RefCell<SimpleNeuron>>> {
         let neuron = SimpleNeuron::new(id);
+        neuron.borrow_mut().set_activation_policy(self.activation_policy.clone());
+        neuron.borrow_mut().trace = self.trace.clone();
         let neuron_id = neuron.borrow().id().clone();
         if let Err(_) = self.neurons.try_insert(neuron_id.clone(), neuron.clone()) {
             log::error!("neuron id: {:?} already exsists in magds, skipping", neuron_id);
             None
         } else {
+            self.neurons_order.push(neuron_id);
             Some(neuron)
         }
     }
@@ -155,6 +422,9 @@ impl MAGDS {
             log::error!("neuron id: {:?} already exsists in magds, skipping", neuron_id);
             None
         } else {
+            neuron.borrow_mut().set_activation_policy(self.activation_policy.clone());
+            neuron.borrow_mut().trace = self.trace.clone();
+            self.neurons_order.push(neuron_id);
             Some(neuron)
         }
     }
@@ -167,9 +437,396 @@ impl MAGDS {
         Some(self.neurons.get(&NeuronID::new(id, parent_id))?.clone())
     }
 
+    pub fn record(&self, id: &NeuronID) -> Option<Record> {
+        Some(Record::from_neuron(&self.neurons.get(id)?.borrow()))
+    }
+
+    pub fn records(&self, parent_id: Rc<str>) -> impl Iterator<Item = Record> + '_ {
+        self.neurons_order.iter()
+            .filter(move |id| id.parent_id == parent_id)
+            .map(|id| Record::from_neuron(&self.neurons[id].borrow()))
+    }
+
+    pub(crate) fn activate_neuron_elements(
+        &mut self, id: &NeuronID, signal: f32, fuzzy: bool
+    ) -> Option<HashMap<NeuronID, Rc<RefCell<dyn Neuron>>>> {
+        let elements = self.neurons.get(id)?.borrow().explain();
+
+        self.next_trace_step();
+        let mut neurons: HashMap<NeuronID, Rc<RefCell<dyn Neuron>>> = HashMap::new();
+        for (element_id, element) in elements {
+            let propagate_horizontal = fuzzy && matches!(
+                self.sensor_data_category(element_id.parent_id.clone()),
+                Some(DataCategory::Numerical) | Some(DataCategory::Ordinal)
+            );
+            neurons.extend(self.activate_element(
+                id, &element, signal, propagate_horizontal, true, ActivationDirection::Stimulus
+            ));
+            if propagate_horizontal { self.activated_sensors.insert(element_id.parent_id.clone()); }
+            self.activated_elements.insert(element_id, element);
+        }
+        self.track_neurons(&neurons);
+        Some(neurons)
+    }
+
+    pub fn similar_to(
+        &mut self, id: &NeuronID, k: usize, fuzzy: bool
+    ) -> Option<Vec<SimilarNeuron>> {
+        let query_elements = self.neurons.get(id)?.borrow().explain();
+        let max_activation: f32 = query_elements.values()
+            .map(|element| 1.0_f32 / element.borrow().counter() as f32)
+            .sum();
+
+        let activated = self.activate_neuron_elements(id, 1.0_f32, fuzzy)?;
+        let mut similar: Vec<SimilarNeuron> = Vec::new();
+        for (neuron_id, neuron) in activated {
+            if neuron_id == *id || !self.neurons.contains_key(&neuron_id) { continue }
+            let neuron = neuron.borrow();
+            let mut shared: Vec<NeuronID> = neuron.explain().into_keys()
+                .filter(|element_id| query_elements.contains_key(element_id))
+                .collect();
+            shared.sort_by(|a, b| (&a.parent_id, &a.id).cmp(&(&b.parent_id, &b.id)));
+            similar.push(SimilarNeuron {
+                neuron: neuron_id,
+                similarity: (neuron.activation() / max_activation).min(1.0_f32),
+                shared
+            });
+        }
+        self.deactivate();
+
+        similar.sort_by(|a, b| OrderedFloat(b.similarity).cmp(&OrderedFloat(a.similarity)));
+        similar.truncate(k);
+        Some(similar)
+    }
+
+    pub fn feature_weights(&self) -> &HashMap<Rc<str>, f32> { &self.feature_weights }
+
+    pub fn feature_weight(&self, id: Rc<str>) -> Option<f32> {
+        self.feature_weights.get(&id).copied()
+    }
+
+    pub fn set_feature_weights(&mut self, weights: HashMap<Rc<str>, f32>) {
+        self.feature_weights = weights;
+    }
+
+    pub fn fuzzy_activation(&self, id: Rc<str>) -> Option<&FuzzyActivation> {
+        self.fuzzy_activations.get(&id)
+    }
+
+    pub fn set_fuzzy_activation(
+        &mut self, id: Rc<str>, fuzzy_activation: FuzzyActivation
+    ) -> Result<(), String> {
+        match self.sensor_data_category(id.clone()) {
+            Some(DataCategory::Numerical) | Some(DataCategory::Ordinal) => {
+                self.fuzzy_activations.insert(id, fuzzy_activation);
+                Ok(())
+            }
+            Some(DataCategory::Categorical) => {
+                Err(format!("sensor {id} is categorical, fuzzy activation is not supported"))
+            }
+            None => Err(format!("sensor {id} doesn't exists"))
+        }
+    }
+
+    pub fn remove_fuzzy_activation(&mut self, id: Rc<str>) -> Option<FuzzyActivation> {
+        self.fuzzy_activations.remove(&id)
+    }
+
+    pub(crate) fn sensor_fuzzy_activate(
+        &mut self, id: Rc<str>, item: &DataTypeValue, signal: f32
+    ) -> Option<HashMap<NeuronID, Rc<RefCell<dyn Neuron>>>> {
+        let value = fuzzy::numeric_value(item)?;
+        let weights = self.kernel_weights(&id, value)?;
+        self.activate_weighted(NeuronID::new(&value.to_string(), &id), weights, signal, false)
+    }
+
+    pub(crate) fn sensor_activate_nearest(
+        &mut self, id: Rc<str>, item: &DataTypeValue, signal: f32
+    ) -> Option<HashMap<NeuronID, Rc<RefCell<dyn Neuron>>>> {
+        let value = fuzzy::numeric_value(item)?;
+        let stimuli: Vec<(SensorElement, f32)> = self.nearest_stimuli(&id, item)?
+            .into_iter()
+            .map(|(_key, element, weight)| (element, weight))
+            .collect();
+        self.activate_weighted(NeuronID::new(&value.to_string(), &id), stimuli, signal, true)
+    }
+
+    fn activate_weighted(
+        &mut self, 
+        source: NeuronID, 
+        weights: Vec<(SensorElement, f32)>, 
+        signal: f32, 
+        propagate_horizontal: bool
+    ) -> Option<HashMap<NeuronID, Rc<RefCell<dyn Neuron>>>> {
+        self.next_trace_step();
+        let mut neurons: HashMap<NeuronID, Rc<RefCell<dyn Neuron>>> = HashMap::new();
+        for ((element, _defined_neurons), weight) in weights {
+            neurons.extend(self.activate_element(
+                &source, 
+                &element, 
+                signal * weight, 
+                propagate_horizontal, 
+                true, 
+                ActivationDirection::Stimulus
+            ));
+            let element_id = element.borrow().id();
+            if propagate_horizontal { self.activated_sensors.insert(element_id.parent_id.clone()); }
+            self.activated_elements.insert(element_id, element);
+        }
+        self.track_neurons(&neurons);
+        Some(neurons)
+    }
+
+    pub(crate) fn kernel_weights(
+        &self, id: &Rc<str>, value: f64
+    ) -> Option<Vec<(SensorElement, f32)>> {
+        let fuzzy_activation = self.fuzzy_activations.get(id)?;
+        let support = fuzzy_activation.support();
+        let (lower, upper) = if support.is_finite() {
+            (Bound::Included(value - support), Bound::Included(value + support))
+        } else {
+            (Bound::Unbounded, Bound::Unbounded)
+        };
+        let elements = self.numeric_range(id, lower, upper)?;
+        Some(
+            elements.into_iter()
+                .filter_map(|(element_value, element)| {
+                    let weight = fuzzy_activation.weight(element_value - value);
+                    if weight == 0.0f32 { None } else { Some((element, weight)) }
+                })
+                .collect()
+        )
+    }
+
+    pub(crate) fn numeric_range(
+        &self, id: &Rc<str>, lower: Bound<f64>, upper: Bound<f64>
+    ) -> Option<Vec<(f64, SensorElement)>> {
+        let sensor = self.sensors.get(id)?.borrow();
+        let data_type = sensor.data_type();
+        let typed = |bound: Bound<f64>| -> Option<Bound<DataTypeValue>> {
+            match bound {
+                Bound::Included(value) => {
+                    Some(Bound::Included(fuzzy::typed_value(value, &data_type)?))
+                }
+                Bound::Excluded(value) => {
+                    Some(Bound::Excluded(fuzzy::typed_value(value, &data_type)?))
+                }
+                Bound::Unbounded => Some(Bound::Unbounded)
+            }
+        };
+        let (lower, upper) = (typed(lower)?, typed(upper)?);
+        let elements = sensor.keyed_range(lower.as_ref(), upper.as_ref()).ok()?;
+        Some(
+            elements.into_iter()
+                .filter_map(|(key, element)| Some((fuzzy::numeric_value(&key)?, element)))
+                .collect()
+        )
+    }
+
+    pub(crate) fn nearest_stimuli(
+        &self, id: &Rc<str>, item: &DataTypeValue
+    ) -> Option<Vec<(DataTypeValue, SensorElement, f32)>> {
+        let value = fuzzy::numeric_value(item)?;
+        let nearest = self.sensors.get(id)?.borrow().nearest(item).ok()??;
+        let min = fuzzy::numeric_value(&nearest.min)?.min(value);
+        let max = fuzzy::numeric_value(&nearest.max)?.max(value);
+        let range = max - min;
+
+        let neighbours: Vec<(f64, DataTypeValue, SensorElement)> = nearest.elements.into_iter()
+            .filter_map(|(key, element)| {
+                Some(((fuzzy::numeric_value(&key)? - value).abs(), key, element))
+            })
+            .collect();
+        let total_distance: f64 = neighbours.iter().map(|(distance, _, _)| distance).sum();
+        let neighbours_count = neighbours.len();
+        Some(
+            neighbours.into_iter()
+                .map(|(distance, key, element)| {
+                    let share = if neighbours_count < 2 || total_distance == 0.0 {
+                        1.0
+                    } else {
+                        1.0 - distance / total_distance
+                    };
+                    let weight = if range > 0.0 { 1.0 - distance / range } else { 1.0 };
+                    (key, element, (share * weight) as f32)
+                })
+                .collect()
+        )
+    }
+
+    pub fn activation_policy(&self) -> Rc<dyn ActivationPolicy> {
+        self.activation_policy.clone()
+    }
+
+    pub fn set_activation_policy(&mut self, policy: Rc<dyn ActivationPolicy>) {
+        for neuron in self.neurons.values() {
+            neuron.borrow_mut().set_activation_policy(policy.clone());
+        }
+        self.activation_policy = policy;
+    }
+
+    pub fn association_matrix(&self) -> Result<DataFrame, String> {
+        association::association_matrix(self, Correlation::Pearson)
+    }
+
+    pub fn association_matrix_with(&self, correlation: Correlation) -> Result<DataFrame, String> {
+        association::association_matrix(self, correlation)
+    }
+
+    pub fn decay(&mut self, factor: f32) {
+        let decay_element = |element: &Rc<RefCell<dyn Neuron>>| {
+            let activation = element.borrow().activation();
+            if activation == 0.0f32 { return }
+            let mut element = element.borrow_mut();
+            element.deactivate(false, false);
+            element.activate(activation * factor, false, false);
+        };
+
+        for sensor_id in &self.activated_sensors {
+            let sensor = match self.sensors.get(sensor_id) {
+                Some(sensor) => sensor,
+                None => continue
+            };
+            for (element, _defined_neurons) in sensor.borrow().elements() {
+                decay_element(&element);
+            }
+        }
+        for (element_id, element) in &self.activated_elements {
+            if self.activated_sensors.contains(&element_id.parent_id) { continue }
+            decay_element(element);
+        }
+        for neuron_id in &self.activated_neurons {
+            if let Some(neuron) = self.neurons.get(neuron_id) {
+                neuron.borrow_mut().activation *= factor;
+            }
+        }
+    }
+
+    pub fn activation_decay(&self) -> Option<ActivationDecay> { self.activation_decay }
+
+    pub fn set_activation_decay(&mut self, activation_decay: Option<ActivationDecay>) {
+        self.activation_decay = activation_decay;
+        self.last_activation = None;
+    }
+
+    fn auto_decay(&mut self) {
+        let now = Instant::now();
+        if let (Some(activation_decay), Some(last_activation)) = (
+            self.activation_decay, self.last_activation
+        ) {
+            let elapsed = now.duration_since(last_activation).as_secs_f32();
+            self.decay(activation_decay.factor(elapsed));
+        }
+        self.last_activation = Some(now);
+    }
+
+    pub fn spread(
+        &mut self, id: Rc<str>, item: &DataTypeValue, signal: f32, options: &SpreadingOptions
+    ) -> Result<SpreadingActivation, String> {
+        let before = self.neuron_activations();
+        self.sensor_stimulate(id.clone(), item, signal, options.fuzzy, true)?;
+        let mut frontier = self.activation_deltas(&before);
+        let mut fired: HashSet<NeuronID> = frontier.iter().map(|(id, _)| id.clone()).collect();
+
+        for _hop in 0..options.hops {
+            if frontier.is_empty() { break }
+            let before = self.neuron_activations();
+            for (neuron_id, delta) in &frontier {
+                let signal = delta * options.attenuation;
+                self.activate_neuron_elements(neuron_id, signal, options.fuzzy);
+            }
+            frontier = self.activation_deltas(&before);
+            frontier.retain(|(id, _)| !fired.contains(id));
+            fired.extend(frontier.iter().map(|(id, _)| id.clone()));
+        }
+
+        let mut elements: HashMap<Rc<str>, Vec<(NeuronID, f32)>> = HashMap::new();
+        for (sensor_id, sensor) in &self.sensors {
+            let mut activated: Vec<(NeuronID, f32)> = sensor.borrow().elements().into_iter()
+                .map(|(element, _)| (element.borrow().id(), element.borrow().activation()))
+                .filter(|(_id, activation)| *activation > 0.0f32)
+                .collect();
+            activated.sort_by(|a, b| {
+                (OrderedFloat(b.1), &a.0.id).cmp(&(OrderedFloat(a.1), &b.0.id))
+            });
+            elements.insert(sensor_id.clone(), activated);
+        }
+
+        Ok(SpreadingActivation { elements, neurons: self.active_neurons() })
+    }
+
+    fn neuron_activations(&self) -> HashMap<NeuronID, f32> {
+        self.neurons.iter()
+            .map(|(id, neuron)| (id.clone(), neuron.borrow().activation()))
+            .collect()
+    }
+
+    fn activation_deltas(&self, before: &HashMap<NeuronID, f32>) -> Vec<(NeuronID, f32)> {
+        let mut deltas: Vec<(NeuronID, f32)> = self.neurons.iter()
+            .filter_map(|(id, neuron)| {
+                let delta = neuron.borrow().activation() - before.get(id).copied()?;
+                if delta > 0.0f32 { Some((id.clone(), delta)) } else { None }
+            })
+            .collect();
+        deltas.sort_by(|a, b| a.0.id.cmp(&b.0.id));
+        deltas
+    }
+
+    pub fn inhibition(&self) -> Option<Inhibition> { self.inhibition }
+
+    pub fn set_inhibition(&mut self, inhibition: Option<Inhibition>) {
+        self.inhibition = inhibition;
+    }
+
+    pub fn inhibit(&mut self, inhibition: Inhibition) {
+        let activations: Vec<(NeuronID, f32)> = self.neurons.iter()
+            .map(|(id, neuron)| (id.clone(), neuron.borrow().activation()))
+            .collect();
+        for (id, activation) in inhibition.inhibit_groups(activations) {
+            self.neurons[&id].borrow_mut().activation = activation;
+        }
+    }
+
+    pub(crate) fn apply_inhibition(&mut self) {
+        if let Some(inhibition) = self.inhibition { self.inhibit(inhibition); }
+    }
+
+    pub fn active_neurons(&self) -> Vec<(NeuronID, f32)> {
+        let mut active: Vec<(NeuronID, f32)> = self.neurons.iter()
+            .map(|(id, neuron)| (id.clone(), neuron.borrow().activation()))
+            .filter(|(_id, activation)| *activation > 0.0f32)
+            .collect();
+        active.sort_by(|a, b| {
+            (OrderedFloat(b.1), &a.0.id).cmp(&(OrderedFloat(a.1), &b.0.id))
+        });
+        active
+    }
+
+    pub fn records_count(&self, parent_id: &Rc<str>) -> usize {
+        self.neurons.iter()
+            .filter(|(id, _neuron)| id.parent_id == *parent_id)
+            .map(|(_id, neuron)| neuron.borrow().counter())
+            .sum()
+    }
+
+    pub(crate) fn element_records(&self, parent_id: &Rc<str>) -> HashMap<NeuronID, usize> {
+        let mut records: HashMap<NeuronID, usize> = HashMap::new();
+        for (id, neuron) in &self.neurons {
+            if id.parent_id != *parent_id { continue }
+            let neuron = neuron.borrow();
+            for element_id in neuron.explain().into_keys() {
+                *records.entry(element_id).or_insert(0) += neuron.counter();
+            }
+        }
+        records
+    }
+
     pub fn deactivate(&mut self) {
         for sensor in &mut self.sensors.values() { sensor.borrow_mut().deactivate_sensor(); }
         for neuron in &mut self.neurons.values() { neuron.borrow_mut().deactivate(false, false); }
+        self.activated_elements.clear();
+        self.activated_sensors.clear();
+        self.activated_neurons.clear();
     } 
 }
 
@@ -198,8 +855,10 @@ impl Display for MAGDS {
 #[cfg(test)]
 mod tests {
     use std::{
+        collections::HashMap,
         rc::Rc,
-        cell::RefCell
+        cell::RefCell,
+        time::{ Instant, Duration }
     };
 
     use asa_graphs::neural::graph::ASAGraph;
@@ -207,15 +866,27 @@ mod tests {
     use bionet_common::{
         neuron::NeuronID,
         sensor::Sensor,
-        data::DataType,
+        data::{ DataType, DataTypeValue },
         polars as polars_common
     };
     
-    use crate::neuron::simple_neuron::SimpleNeuron;
+    use crate::{
+        neuron::{
+            simple_neuron::SimpleNeuron,
+            activation::{ NormalizedActivation, SaturatingActivation }
+        },
+        algorithm::predict
+    };
 
     use super::{
         MAGDS,
-        super::parser
+        SpreadingOptions,
+        super::{
+            parser,
+            trace::{ ActivationEvent, ActivationDirection },
+            decay::ActivationDecay,
+            inhibition::Inhibition
+        }
     };
 
     #[test]
@@ -351,4 +1022,411 @@ mod tests {
         assert_eq!(neuron_15.borrow().activation(), 0.0_f32);
         assert_eq!(neuron_16.borrow().activation(), 0.0_f32);
     }
+
+    #[test]
+    fn activation_policy() {
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        magds.set_activation_policy(Rc::new(NormalizedActivation));
+
+        let sl58 = magds.sensor_search("sepal.length".into(), &5.8_f64.into()).unwrap();
+        let neuron_15 = magds.neuron("15", "iris").unwrap();
+        let defining_sensors = neuron_15.borrow().explain().len() as f32;
+
+        sl58.borrow_mut().activate(1.0, false, true);
+        assert_eq!(neuron_15.borrow().activation(), 1.0_f32 / 7_f32 / defining_sensors);
+        magds.deactivate();
+
+        let mut lists = parser::magds_from_csv("lists", "data/lists.csv").unwrap();
+        lists.set_activation_policy(Rc::new(NormalizedActivation));
+        let record = lists.neurons.values().next().unwrap().clone();
+        assert_eq!(record.borrow().explain().len(), 6);
+        for value in ["a", "b"] {
+            lists.sensor_activate("x".into(), &value.to_string().into(), 1.0, false, true).unwrap();
+        }
+        // both list items of x come from one of the three defining sensors
+        assert!((record.borrow().activation() - 2.0_f32 / 3.0_f32).abs() < 1e-6);
+
+        let neuron = magds.create_neuron(NeuronID::new("new", "iris")).unwrap();
+        magds.sensor_insert_connected("sepal.length".into(), &5.8_f64.into(), &neuron).unwrap();
+        magds.set_activation_policy(
+            Rc::new(SaturatingActivation { threshold: 0.0, saturation: 0.1 })
+        );
+        sl58.borrow_mut().activate(1.0, false, true);
+        assert_eq!(neuron.borrow().activation(), 0.1_f32);
+        assert_eq!(neuron_15.borrow().activation(), 0.1_f32);
+    }
+
+    #[test]
+    fn feature_weights() {
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let sl58 = magds.sensor_search("sepal.length".into(), &5.8_f64.into()).unwrap();
+        let neuron_15 = magds.neuron("15", "iris").unwrap();
+
+        magds.set_feature_weights(HashMap::from([("sepal.length".into(), 0.5_f32)]));
+        magds.sensor_activate("sepal.length".into(), &5.8_f64.into(), 1.0, false, true).unwrap();
+        assert_eq!(sl58.borrow().activation(), 1.0_f32);
+        magds.deactivate();
+
+        let features: Vec<(Rc<str>, DataTypeValue)> = vec![
+            ("sepal.length".into(), 5.8_f64.into())
+        ];
+        predict::predict(&mut magds, &features, "variety".into(), false).unwrap();
+        assert_eq!(sl58.borrow().activation(), 0.5_f32);
+        assert_eq!(neuron_15.borrow().activation(), 0.5_f32 / 7_f32);
+        magds.deactivate();
+
+        let weighted: Vec<(Rc<str>, DataTypeValue, f32)> = vec![
+            ("sepal.length".into(), 5.8_f64.into(), 0.25_f32)
+        ];
+        predict::predict_weighted(&mut magds, weighted, "variety".into(), false).unwrap();
+        assert_eq!(sl58.borrow().activation(), 0.25_f32);
+        magds.deactivate();
+
+        magds.similar_to(&NeuronID::new("15", "iris"), 3, false).unwrap();
+        assert_eq!(sl58.borrow().activation(), 1.0_f32);
+    }
+
+    #[test]
+    fn activation_trace() {
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        assert!(magds.trace().is_none());
+        magds.sensor_activate("sepal.length".into(), &5.8_f64.into(), 1.0, true, true).unwrap();
+        assert!(magds.trace().is_none());
+        magds.deactivate();
+
+        magds.start_trace();
+        magds.sensor_activate("sepal.length".into(), &5.8_f64.into(), 1.0, true, true).unwrap();
+        magds.sensor_activate("variety".into(), &"Setosa".to_string().into(), 1.0, false, true)
+            .unwrap();
+        let trace = magds.stop_trace().unwrap();
+        assert!(magds.trace().is_none());
+        assert_eq!(trace.steps(), 2);
+
+        let stimulus: Vec<&ActivationEvent> = trace.events.iter()
+            .filter(|event| event.direction == ActivationDirection::Stimulus)
+            .collect();
+        assert_eq!(stimulus.len(), 2);
+        assert_eq!(stimulus[0].target, NeuronID::new("5.8", "sepal.length"));
+        assert_eq!(stimulus[0].activation, 1.0);
+
+        let sl57 = trace.events.iter()
+            .find(|event| event.target == NeuronID::new("5.7", "sepal.length"))
+            .unwrap();
+        assert_eq!(sl57.direction, ActivationDirection::Horizontal);
+        assert_eq!(sl57.activation, 0.9722222_f32);
+
+        let neuron_15 = trace.events.iter()
+            .find(|event| event.target == NeuronID::new("15", "iris") && event.step == 1)
+            .unwrap();
+        assert_eq!(neuron_15.direction, ActivationDirection::Vertical);
+        assert_eq!(neuron_15.source, NeuronID::new("5.8", "sepal.length"));
+        assert_eq!(neuron_15.signal, 1.0_f32 / 7_f32);
+        assert_eq!(neuron_15.activation, 1.0_f32 / 7_f32);
+        assert!(magds.neuron("15", "iris").unwrap().borrow().activation() > neuron_15.activation);
+
+        let summary = trace.summary();
+        assert_eq!(summary["variety"].stimuli, 1);
+        assert_eq!(summary["variety"].horizontal, 0);
+        assert_eq!(summary["variety"].vertical, 49);
+        assert!(summary["sepal.length"].horizontal > 0);
+
+        let json = trace.to_json();
+        assert_eq!(json["steps"], 2);
+        assert_eq!(json["events"].as_array().unwrap().len(), trace.events.len());
+        assert!(trace.to_json_string().contains("\"direction\":\"stimulus\""));
+
+        magds.deactivate();
+        magds.start_trace();
+        magds.sensor_activate("sepal.length".into(), &5.8_f64.into(), 1.0, true, false).unwrap();
+        let trace = magds.stop_trace().unwrap();
+        assert!(trace.events.iter().any(|event| {
+            event.direction == ActivationDirection::Horizontal
+        }));
+        assert!(trace.events.iter().all(|event| {
+            event.direction != ActivationDirection::Vertical
+        }));
+
+        magds.deactivate();
+        magds.start_trace();
+        let neuron_15 = NeuronID::new("15", "iris");
+        magds.similar_to(&neuron_15, 3, false).unwrap();
+        let trace = magds.stop_trace().unwrap();
+        assert_eq!(trace.steps(), 1);
+        let stimulus: Vec<&ActivationEvent> = trace.events.iter()
+            .filter(|event| event.direction == ActivationDirection::Stimulus)
+            .collect();
+        assert_eq!(stimulus.len(), 5);
+        assert!(stimulus.iter().all(|event| event.source == neuron_15));
+        assert!(trace.events.iter().all(|event| {
+            event.direction != ActivationDirection::Horizontal
+        }));
+
+        magds.start_trace();
+        let options = SpreadingOptions { hops: 2, ..SpreadingOptions::default() };
+        magds.spread("variety".into(), &"Setosa".to_string().into(), 1.0, &options).unwrap();
+        let trace = magds.stop_trace().unwrap();
+        assert!(trace.steps() > 1);
+        assert!(trace.events.iter().any(|event| event.source.parent_id == "iris".into()));
+    }
+
+    #[test]
+    fn decay() {
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let sl58 = magds.sensor_search("sepal.length".into(), &5.8_f64.into()).unwrap();
+        let neuron_15 = magds.neuron("15", "iris").unwrap();
+
+        magds.sensor_activate("sepal.length".into(), &5.8_f64.into(), 1.0, false, true).unwrap();
+        magds.decay(0.5);
+        assert_eq!(sl58.borrow().activation(), 0.5_f32);
+        assert_eq!(neuron_15.borrow().activation(), 0.5_f32 / 7_f32);
+        magds.deactivate();
+
+        magds.set_activation_decay(Some(ActivationDecay::PerStep(0.5)));
+        magds.sensor_activate("sepal.length".into(), &5.8_f64.into(), 1.0, false, true).unwrap();
+        assert_eq!(sl58.borrow().activation(), 1.0_f32);
+        magds.sensor_activate("sepal.length".into(), &5.8_f64.into(), 1.0, false, true).unwrap();
+        assert_eq!(sl58.borrow().activation(), 1.5_f32);
+        assert!((neuron_15.borrow().activation() - 1.5_f32 / 7_f32).abs() < 1e-6);
+        magds.deactivate();
+
+        magds.set_activation_decay(Some(ActivationDecay::PerSecond(0.5)));
+        magds.sensor_activate("sepal.length".into(), &5.8_f64.into(), 1.0, false, true).unwrap();
+        magds.last_activation = Instant::now().checked_sub(Duration::from_secs(2));
+        magds.sensor_activate("sepal.length".into(), &5.8_f64.into(), 1.0, false, true).unwrap();
+        let activation = sl58.borrow().activation();
+        assert!(activation > 1.2 && activation <= 1.25);
+
+        magds.set_activation_decay(None);
+        magds.sensor_activate("sepal.length".into(), &5.8_f64.into(), 1.0, false, true).unwrap();
+        assert_eq!(sl58.borrow().activation(), activation + 1.0);
+        magds.deactivate();
+
+        magds.sensor_activate("sepal.length".into(), &5.8_f64.into(), 1.0, true, true).unwrap();
+        magds.sensor_activate("variety".into(), &"Setosa".to_string().into(), 1.0, false, true)
+            .unwrap();
+        assert_eq!(magds.activated_elements.len(), 2);
+        assert!(magds.activated_sensors.contains("sepal.length"));
+        let sl57 = magds.sensor_search("sepal.length".into(), &5.7_f64.into()).unwrap();
+        let sl57_activation = sl57.borrow().activation();
+        magds.decay(0.5);
+        assert_eq!(sl57.borrow().activation(), sl57_activation * 0.5);
+        let setosa = magds.sensor_search("variety".into(), &"Setosa".to_string().into()).unwrap();
+        assert_eq!(setosa.borrow().activation(), 0.5_f32);
+        magds.deactivate();
+        assert!(magds.activated_neurons.is_empty());
+    }
+
+    #[test]
+    fn inhibition() {
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let activate = |magds: &mut MAGDS| {
+            magds.sensor_activate("sepal.length".into(), &5.8_f64.into(), 1.0, true, true).unwrap();
+            magds.sensor_activate("petal.length".into(), &4.1_f64.into(), 1.0, true, true).unwrap();
+        };
+
+        activate(&mut magds);
+        let active = magds.active_neurons();
+        for window in active.windows(2) { assert!(window[0].1 >= window[1].1); }
+        let max = active[0].1;
+
+        magds.inhibit(Inhibition::Threshold(0.5));
+        let thresholded = magds.active_neurons();
+        assert!(thresholded.len() < active.len());
+        assert!(thresholded.iter().all(|(_id, activation)| *activation >= 0.5 * max));
+        assert_eq!(thresholded[0], active[0]);
+        magds.deactivate();
+
+        activate(&mut magds);
+        magds.inhibit(Inhibition::WinnerTakeAll(3));
+        let winners = magds.active_neurons();
+        assert_eq!(winners.len(), 3);
+        assert_eq!(winners[0], active[0]);
+        magds.deactivate();
+
+        activate(&mut magds);
+        magds.inhibit(Inhibition::Lateral(1.0));
+        let lateral = magds.active_neurons();
+        assert!(lateral.len() < active.len());
+        assert_eq!(lateral[0].1, max);
+        magds.deactivate();
+
+        magds.set_inhibition(Some(Inhibition::WinnerTakeAll(1)));
+        let features = vec![
+            ("sepal.length".into(), 5.8_f64.into(), 1.0_f32),
+            ("petal.length".into(), 4.1_f64.into(), 1.0_f32)
+        ];
+        predict::activate_features(&mut magds, &features, true);
+        assert_eq!(magds.active_neurons().len(), 1);
+    }
+
+    #[test]
+    fn spread() {
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let setosa: DataTypeValue = "Setosa".to_string().into();
+
+        let options = SpreadingOptions { hops: 0, ..SpreadingOptions::default() };
+        let spread = magds.spread("variety".into(), &setosa, 1.0, &options).unwrap();
+        assert_eq!(spread.neurons.len(), 49);
+        assert!(spread.elements["petal.length"].is_empty());
+        assert_eq!(spread.elements["variety"].len(), 1);
+        magds.deactivate();
+
+        let spread = magds.spread(
+            "variety".into(), &setosa, 1.0, &SpreadingOptions::default()
+        ).unwrap();
+        let petal_length = &spread.elements["petal.length"];
+        assert!(!petal_length.is_empty());
+        assert!(petal_length.iter().all(|(id, _)| id.id.parse::<f64>().unwrap() < 2.0));
+        for window in petal_length.windows(2) { assert!(window[0].1 >= window[1].1); }
+        assert_eq!(spread.elements["variety"][0].0, NeuronID::new("Setosa", "variety"));
+        magds.deactivate();
+
+        let options = SpreadingOptions { hops: 2, ..SpreadingOptions::default() };
+        let two_hops = magds.spread("variety".into(), &setosa, 1.0, &options).unwrap();
+        assert!(two_hops.neurons.len() > 49);
+        assert!(two_hops.elements["variety"].len() > 1);
+        assert!(magds.spread("unknown".into(), &setosa, 1.0, &options).is_err());
+    }
+
+    #[test]
+    fn spread_hops() {
+        let mut magds = MAGDS::new();
+        magds.create_sensor("a".into(), DataType::String).unwrap();
+        magds.create_sensor("b".into(), DataType::String).unwrap();
+        let rows = [
+            ("1", vec![("a", "x"), ("b", "y")]),
+            ("2", vec![("a", "z"), ("b", "y")]),
+            ("3", vec![("a", "z")])
+        ];
+        for (id, values) in rows {
+            let neuron = magds.create_neuron(NeuronID::new(id, "test")).unwrap();
+            for (sensor, value) in values {
+                let value: DataTypeValue = value.to_string().into();
+                magds.sensor_insert_connected(sensor.into(), &value, &neuron).unwrap();
+            }
+        }
+
+        let options = SpreadingOptions { hops: 2, attenuation: 0.5, fuzzy: false };
+        let spread = magds.spread("a".into(), &"x".to_string().into(), 1.0, &options).unwrap();
+        let neurons: HashMap<NeuronID, f32> = spread.neurons.into_iter().collect();
+        assert_eq!(neurons.len(), 3);
+        assert!((neurons[&NeuronID::new("1", "test")] - 1.8125).abs() < 1e-6);
+        assert!((neurons[&NeuronID::new("2", "test")] - 0.375).abs() < 1e-6);
+        assert!((neurons[&NeuronID::new("3", "test")] - 0.0625).abs() < 1e-6);
+    }
+
+    #[test]
+    fn records() {
+        let magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+
+        let record = magds.record(&NeuronID::new("1", "iris")).unwrap();
+        assert_eq!(record.neuron, NeuronID::new("1", "iris"));
+        assert!(record.value("sepal.length").is_none());
+        assert_eq!(record.value("petal.length").unwrap().as_f64(), Some(&1.4));
+        assert_eq!(
+            record.value("variety").unwrap().as_string().map(|v| v.as_str()), Some("Setosa")
+        );
+        assert_eq!(
+            record.sensors(),
+            vec![
+                Rc::from("petal.length"), Rc::from("petal.width"),
+                Rc::from("sepal.width"), Rc::from("variety")
+            ]
+        );
+        assert!(magds.record(&NeuronID::new("unknown", "iris")).is_none());
+
+        let records: Vec<_> = magds.records("iris".into()).collect();
+        assert_eq!(records.len(), 150);
+        assert_eq!(records[0].neuron, NeuronID::new("1", "iris"));
+        assert_eq!(records[9].neuron, NeuronID::new("10", "iris"));
+        assert_eq!(records[149].neuron, NeuronID::new("150", "iris"));
+        assert_eq!(magds.records("unknown".into()).count(), 0);
+
+        let mut magds = magds;
+        magds.create_neuron(NeuronID::new("0", "iris")).unwrap();
+        let last = magds.records("iris".into()).last().unwrap();
+        assert_eq!(last.neuron, NeuronID::new("0", "iris"));
+
+        let magds = parser::magds_from_csv("lists", "data/lists.csv").unwrap();
+        let record = magds.records("lists".into()).next().unwrap();
+        for sensor in ["x", "y", "z"] {
+            let values = record.get(sensor).unwrap().as_multiple().unwrap();
+            let values: Vec<&str> = values.iter()
+                .map(|v| v.as_string().unwrap().as_str())
+                .collect();
+            assert_eq!(values, vec!["a", "b"]);
+        }
+    }
+
+    #[test]
+    fn similar_to() {
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let query_id = NeuronID::new("2", "iris");
+        let query_elements = magds.neuron_from_id(&query_id).unwrap().borrow().explain();
+
+        let similar = magds.similar_to(&query_id, 5, false).unwrap();
+        assert_eq!(similar.len(), 5);
+        for neighbour in &similar {
+            assert_ne!(neighbour.neuron, query_id);
+            assert!(neighbour.similarity > 0.0 && neighbour.similarity <= 1.0);
+            assert!(!neighbour.shared.is_empty());
+            assert!(neighbour.shared.iter().all(|id| query_elements.contains_key(id)));
+        }
+        for window in similar.windows(2) { assert!(window[0].similarity >= window[1].similarity); }
+
+        let neuron_2 = magds.neuron("2", "iris").unwrap();
+        assert_eq!(neuron_2.borrow().activation(), 0.0_f32);
+
+        let fuzzy_similar = magds.similar_to(&query_id, 5, true).unwrap();
+        assert_eq!(fuzzy_similar.len(), 5);
+        assert!(magds.similar_to(&NeuronID::new("unknown", "iris"), 5, true).is_none());
+    }
+
+    #[test]
+    fn range_query() {
+        let magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let counter = |value: f64| {
+            magds.sensor_search("sepal.length".into(), &value.into()).unwrap().borrow().counter()
+        };
+
+        let result = magds.range_query(
+            "sepal.length".into(), &5.7_f64.into(), &5.9_f64.into(), true
+        ).unwrap();
+        let ids: Vec<Rc<str>> = result.elements.iter().map(|e| e.borrow().id().id).collect();
+        assert_eq!(ids, vec![Rc::from("5.7"), Rc::from("5.8"), Rc::from("5.9")]);
+        assert_eq!(result.neurons.len(), counter(5.7) + counter(5.8) + counter(5.9));
+
+        let result = magds.range_query(
+            "sepal.length".into(), &5.7_f64.into(), &5.9_f64.into(), false
+        ).unwrap();
+        assert_eq!(result.elements.len(), 1);
+        assert_eq!(result.neurons.len(), 7);
+
+        let result = magds.range_query(
+            "sepal.length".into(), &5.65_f64.into(), &5.75_f64.into(), true
+        ).unwrap();
+        assert_eq!(result.elements.len(), 1);
+        assert_eq!(result.elements[0].borrow().id().id, Rc::from("5.7"));
+
+        let result = magds.range_query(
+            "sepal.length".into(), &4.0_f64.into(), &4.35_f64.into(), true
+        ).unwrap();
+        assert_eq!(result.elements.len(), 1);
+        assert_eq!(result.elements[0].borrow().id().id, Rc::from("4.3"));
+
+        let result = magds.greater_than("sepal.length".into(), &7.7_f64.into(), false).unwrap();
+        assert_eq!(result.elements.len(), 1);
+        assert_eq!(result.elements[0].borrow().id().id, Rc::from("7.9"));
+
+        let result = magds.less_than("sepal.length".into(), &4.4_f64.into(), true).unwrap();
+        let ids: Vec<Rc<str>> = result.elements.iter().map(|e| e.borrow().id().id).collect();
+        assert_eq!(ids, vec![Rc::from("4.3"), Rc::from("4.4")]);
+
+        let result = magds.range_query(
+            "sepal.length".into(), &"a".to_string().into(), &5.9_f64.into(), true
+        );
+        assert!(result.is_err());
+        let result = magds.greater_than("unknown".into(), &1.0_f64.into(), true);
+        assert!(result.is_err());
+    }
 }
\ No newline at end of file
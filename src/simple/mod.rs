@@ -1,3 +1,10 @@
 pub mod magds;
 pub mod sensor;
-pub mod parser;
\ No newline at end of file
+pub mod parser;
+pub mod fuzzy;
+pub mod trace;
+pub mod decay;
+pub mod inhibition;
+pub mod context;
+pub mod record;
+pub mod builder;
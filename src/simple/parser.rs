@@ -1,11 +1,13 @@
 use std::{
     rc::Rc,
     cell::RefCell,
+    collections::HashMap,
     marker::PhantomData,
     path::Path
 };
 
 use regex::Regex;
+use ordered_float::OrderedFloat;
 
 use polars::prelude::*;
 
@@ -88,7 +90,10 @@ pub(crate) fn sensor_from_datavec(
 }
 
 pub(crate) fn connected_sensor_from_datavec(
-    mut magds: &mut MAGDS, id: &str, data: &DataVecOption, neurons: &[Rc<RefCell<SimpleNeuron>>]
+    mut magds: &mut MAGDS, 
+    id: &str, 
+    data: &DataVecOption, 
+    neurons: &[Option<Rc<RefCell<SimpleNeuron>>>]
 ) -> Option<Rc<RefCell<SensorConatiner>>> {  
     match data {
         DataVecOption::Unknown => {
@@ -110,8 +115,53 @@ pub(crate) fn connected_sensor_from_datavec(
     }
 }
 
+pub(crate) fn datavec_value(data: &DataVecOption, index: usize) -> Option<DataTypeValue> {
+    match data {
+        DataVecOption::Unknown => None,
+        DataVecOption::BoolVec(vec) => Some((*vec.get(index)?)?.into()),
+        DataVecOption::UInt8Vec(vec) => Some((*vec.get(index)?)?.into()),
+        DataVecOption::UInt16Vec(vec) => Some((*vec.get(index)?)?.into()),
+        DataVecOption::UInt32Vec(vec) => Some((*vec.get(index)?)?.into()),
+        DataVecOption::UInt64Vec(vec) => Some((*vec.get(index)?)?.into()),
+        DataVecOption::Int8Vec(vec) => Some((*vec.get(index)?)?.into()),
+        DataVecOption::Int16Vec(vec) => Some((*vec.get(index)?)?.into()),
+        DataVecOption::Int32Vec(vec) => Some((*vec.get(index)?)?.into()),
+        DataVecOption::Int64Vec(vec) => Some((*vec.get(index)?)?.into()),
+        DataVecOption::Float32Vec(vec) => Some((*vec.get(index)?)?.into()),
+        DataVecOption::Float64Vec(vec) => Some((*vec.get(index)?)?.into()),
+        DataVecOption::Utf8Vec(vec) => {
+            let value = vec.get(index)?.clone()?;
+            if value.is_empty() { return None }
+            Some(value.into())
+        }
+    }
+}
+
+pub(crate) fn parse_list(key: &str) -> Result<Option<Vec<String>>, String> {
+    let list = match key.strip_prefix("[").and_then(|key| key.strip_suffix("]")) {
+        Some(list) => list,
+        None => return Ok(None)
+    };
+    if list.trim().is_empty() { return Ok(Some(Vec::new())) }
+    let quotes = Regex::new(r#"["']+"#).unwrap();
+    let key_vec: Vec<String> = Regex::new(r"\s*,\s*")
+        .unwrap()
+        .split(list)
+        .map(|x| {
+            quotes.split(x)
+                .find(|x| *x != "")
+                .map(|x| x.to_string())
+                .ok_or(format!("empty item in list {key}"))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Some(key_vec))
+}
+
 fn connector_string(
-    magds: &mut MAGDS, id: &str, vec: &[Option<String>], neurons: &[Rc<RefCell<SimpleNeuron>>]
+    magds: &mut MAGDS, 
+    id: &str, 
+    vec: &[Option<String>], 
+    neurons: &[Option<Rc<RefCell<SimpleNeuron>>>]
 ) -> Option<Rc<RefCell<SensorConatiner>>> 
 where 
     PhantomData<String>: DataDeductor, 
@@ -124,21 +174,20 @@ where
         if let Some(key) = key {
             if key == "" { continue }
 
-            let neuron_ptr = neurons[i].clone();
+            let neuron_ptr = match &neurons[i] {
+                Some(neuron_ptr) => neuron_ptr.clone(),
+                None => continue
+            };
             let mut neuron = neuron_ptr.borrow_mut();
 
-            if key.starts_with("[") && key.ends_with("]") {
-                let key = key.strip_prefix("[").unwrap().strip_suffix("]").unwrap();
-                let key_vec: Vec<_> = Regex::new(r"\s*,\s*")
-                    .unwrap()
-                    .split(key)
-                    .map(|x| {
-                        Regex::new(r#"["']+"#).unwrap()
-                            .split(x)
-                            .filter(|x| *x != "")
-                            .next().unwrap()
-                            .to_string()
-                    }).collect();
+            let key_vec = match parse_list(key) {
+                Ok(key_vec) => key_vec,
+                Err(e) => {
+                    log::error!("cannot parse sensor {id} value for neuron {neuron}, error: {e}");
+                    continue
+                }
+            };
+            if let Some(key_vec) = key_vec {
                 for key in key_vec {
                     let element = sensor.insert(&key);
                     if let Err(e) = neuron.connect_bilateral_from(
@@ -171,7 +220,7 @@ where
 }
 
 fn connector<T: SensorData>(
-    magds: &mut MAGDS, id: &str, vec: &[Option<T>], neurons: &[Rc<RefCell<SimpleNeuron>>]
+    magds: &mut MAGDS, id: &str, vec: &[Option<T>], neurons: &[Option<Rc<RefCell<SimpleNeuron>>>]
 ) -> Option<Rc<RefCell<SensorConatiner>>> 
 where 
     PhantomData<T>: DataDeductor, 
@@ -182,7 +231,10 @@ where
     let mut sensor = ASAGraph::<T>::new(id);
     for (i, key) in vec.into_iter().enumerate() {
         if let Some(key) = key {
-            let neuron_ptr = neurons[i].clone();
+            let neuron_ptr = match &neurons[i] {
+                Some(neuron_ptr) => neuron_ptr.clone(),
+                None => continue
+            };
             let mut neuron = neuron_ptr.borrow_mut();
             let element = sensor.insert(key);
             if let Err(e) = neuron.connect_bilateral_from(
@@ -201,32 +253,115 @@ where
     magds.add_sensor(Rc::new(RefCell::new(sensor.into())))
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum RowValue {
+    Bool(bool),
+    Unsigned(u128),
+    Signed(i128),
+    Float(OrderedFloat<f64>),
+    String(Rc<str>)
+}
+
+impl RowValue {
+    pub(crate) fn new(value: &DataTypeValue) -> Option<RowValue> {
+        match value {
+            DataTypeValue::Bool(v) => Some(RowValue::Bool(*v)),
+            DataTypeValue::U8(v) => Some(RowValue::Unsigned(*v as u128)),
+            DataTypeValue::U16(v) => Some(RowValue::Unsigned(*v as u128)),
+            DataTypeValue::U32(v) => Some(RowValue::Unsigned(*v as u128)),
+            DataTypeValue::U64(v) => Some(RowValue::Unsigned(*v as u128)),
+            DataTypeValue::U128(v) => Some(RowValue::Unsigned(*v)),
+            DataTypeValue::USize(v) => Some(RowValue::Unsigned(*v as u128)),
+            DataTypeValue::I8(v) => Some(RowValue::Signed(*v as i128)),
+            DataTypeValue::I16(v) => Some(RowValue::Signed(*v as i128)),
+            DataTypeValue::I32(v) => Some(RowValue::Signed(*v as i128)),
+            DataTypeValue::I64(v) => Some(RowValue::Signed(*v as i128)),
+            DataTypeValue::I128(v) => Some(RowValue::Signed(*v)),
+            DataTypeValue::ISize(v) => Some(RowValue::Signed(*v as i128)),
+            DataTypeValue::F32(v) => Some(RowValue::Float(OrderedFloat(*v as f64))),
+            DataTypeValue::F64(v) => Some(RowValue::Float(OrderedFloat(*v))),
+            DataTypeValue::RcStr(v) => Some(RowValue::String(v.clone())),
+            DataTypeValue::String(v) => Some(RowValue::String(v.as_str().into())),
+            _ => None
+        }
+    }
+}
+
+pub(crate) fn duplicate_rows(
+    rows: usize, row_values: impl Fn(usize) -> Vec<Vec<DataTypeValue>>
+) -> Vec<Option<usize>> {
+    let mut originals: HashMap<Vec<Vec<RowValue>>, usize> = HashMap::new();
+    let mut duplicates: Vec<Option<usize>> = Vec::with_capacity(rows);
+    for row in 0..rows {
+        let row_key: Option<Vec<Vec<RowValue>>> = row_values(row).iter()
+            .map(|values| values.iter().map(RowValue::new).collect())
+            .collect();
+        let duplicate = match row_key {
+            Some(row_key) => match originals.try_insert(row_key, row) {
+                Ok(_) => None,
+                Err(occupied) => Some(*occupied.entry.get())
+            },
+            None => None
+        };
+        duplicates.push(duplicate);
+    }
+    duplicates
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ParserOptions {
+    pub collapse_duplicates: bool
+}
+
 pub fn magds_from_df(df_name: Rc<str>, df: &DataFrame) -> MAGDS {
+    magds_from_df_with(df_name, df, &ParserOptions::default())
+}
+
+pub fn magds_from_df_with(df_name: Rc<str>, df: &DataFrame, options: &ParserOptions) -> MAGDS {
     let mut magds = MAGDS::new();
     
     log::info!("magds_from_df: df size: {} (cols) x {} (rows)", df.width(), df.height());
     log::info!("magds_from_df: df columns: {:?}", df.get_column_names());
-    
-
-    let mut neurons: Vec<Rc<RefCell<SimpleNeuron>>> = Vec::new();
-    for i in 0..df.height() {
-        let neuron = magds.create_neuron(
-            NeuronID{ id: (i + 1).to_string().into(), parent_id: df_name.clone() }
-        ).unwrap();
-        neurons.push(neuron);
-    }
 
+    let mut datavecs: Vec<(&str, DataVecOption)> = Vec::new();
     for column in df.get_columns() {
         let column_name = column.name();
-        let datavec = match polars_common::series_to_datavec(column) {
-            Ok(v) => v,
+        match polars_common::series_to_datavec(column) {
+            Ok(v) => datavecs.push((column_name, v)),
             Err(e) => { 
                 log::error!("error convering {column_name} to datavec, error: {e}");
                 continue
             }
         };
+    }
+
+    let duplicates = if options.collapse_duplicates {
+        duplicate_rows(df.height(), |row| {
+            datavecs.iter()
+                .map(|(_name, datavec)| datavec_value(datavec, row).into_iter().collect())
+                .collect()
+        })
+    } else {
+        vec![None; df.height()]
+    };
+
+    let mut neurons: Vec<Option<Rc<RefCell<SimpleNeuron>>>> = Vec::with_capacity(df.height());
+    for i in 0..df.height() {
+        if let Some(original) = duplicates[i] {
+            if let Some(neuron) = &neurons[original] { neuron.borrow_mut().counter += 1; }
+            neurons.push(None);
+            continue
+        }
+
+        let neuron = magds.create_neuron(
+            NeuronID{ id: (i + 1).to_string().into(), parent_id: df_name.clone() }
+        ).unwrap();
+        neurons.push(Some(neuron));
+    }
+
+    for (column_name, datavec) in &datavecs {
         if let None = connected_sensor_from_datavec(
-            &mut magds, column_name, &datavec, &neurons
+            &mut magds, column_name, datavec, &neurons
         ) {
             log::error!("error convering {column_name} datavec to sensor");
             continue
@@ -237,10 +372,16 @@ pub fn magds_from_df(df_name: Rc<str>, df: &DataFrame) -> MAGDS {
 }
 
 pub fn magds_from_csv(name: &str, file_path: &str) -> Option<MAGDS> {
+    magds_from_csv_with(name, file_path, &ParserOptions::default())
+}
+
+pub fn magds_from_csv_with(
+    name: &str, file_path: &str, options: &ParserOptions
+) -> Option<MAGDS> {
     let path = Path::new(file_path);
     if !path.is_file() || !file_path.ends_with(".csv") { return None }
     let df = polars_common::csv_to_dataframe(file_path).ok()?;
-    let magds = magds_from_df(name.into(), &df);
+    let magds = magds_from_df_with(name.into(), &df, options);
     Some(magds)
 }
 
@@ -268,6 +409,19 @@ mod tests {
         println!("{magds}");
     }
 
+    #[test]
+    fn list_parse() {
+        assert_eq!(super::parse_list("a"), Ok(None));
+        assert_eq!(
+            super::parse_list("['a', \"b\", c]"),
+            Ok(Some(vec!["a".to_string(), "b".to_string(), "c".to_string()]))
+        );
+        assert!(super::parse_list("[a, , b]").is_err());
+        assert!(super::parse_list("[a, '']").is_err());
+        assert_eq!(super::parse_list("[]"), Ok(Some(Vec::new())));
+        assert_eq!(super::parse_list("[ ]"), Ok(Some(Vec::new())));
+    }
+
     #[test]
     fn csv_to_magds() {
         let magds = super::magds_from_csv("iris", "data/iris.csv").unwrap();
@@ -0,0 +1,334 @@
+use std::{
+    collections::HashMap,
+    mem,
+    rc::Rc
+};
+
+use enum_as_inner::EnumAsInner;
+
+use bionet_common::{
+    neuron::{ Neuron, NeuronID },
+    data::{ DataType, DataTypeValue, DataTypeValueStr }
+};
+
+use crate::{
+    neuron::simple_neuron::SimpleNeuron,
+    simple::{ magds::MAGDS, parser::RowValue }
+};
+
+#[derive(Debug, Clone, EnumAsInner)]
+pub enum RecordValue {
+    Single(DataTypeValue),
+    Multiple(Vec<DataTypeValue>)
+}
+
+impl RecordValue {
+    pub fn first(&self) -> Option<&DataTypeValue> {
+        match self {
+            RecordValue::Single(value) => Some(value),
+            RecordValue::Multiple(values) => values.first()
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<DataTypeValue> {
+        match self {
+            RecordValue::Single(value) => vec![value.clone()],
+            RecordValue::Multiple(values) => values.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub neuron: NeuronID,
+    pub values: HashMap<Rc<str>, RecordValue>
+}
+
+impl Record {
+    pub(crate) fn from_neuron(neuron: &SimpleNeuron) -> Record {
+        let mut elements: Vec<(NeuronID, DataTypeValue)> = Vec::new();
+        for (element_id, element) in neuron.explain() {
+            let data_type = element.borrow().data_type();
+            match DataTypeValueStr(&element_id.id).data_type_value(data_type) {
+                Some(value) => elements.push((element_id, value)),
+                None => log::warn!("cannot parse {element_id} value for {}", neuron.id())
+            }
+        }
+        elements.sort_by_cached_key(|(id, value)| (id.parent_id.clone(), RowValue::new(value)));
+
+        let mut values: HashMap<Rc<str>, RecordValue> = HashMap::new();
+        for (element_id, value) in elements {
+            match values.remove(&element_id.parent_id) {
+                None => values.insert(element_id.parent_id, RecordValue::Single(value)),
+                Some(RecordValue::Single(first)) => {
+                    values.insert(element_id.parent_id, RecordValue::Multiple(vec![first, value]))
+                }
+                Some(RecordValue::Multiple(mut all)) => {
+                    all.push(value);
+                    values.insert(element_id.parent_id, RecordValue::Multiple(all))
+                }
+            };
+        }
+
+        Record { neuron: neuron.id(), values }
+    }
+
+    pub fn get(&self, sensor: &str) -> Option<&RecordValue> { self.values.get(sensor) }
+
+    pub fn value(&self, sensor: &str) -> Option<&DataTypeValue> { self.get(sensor)?.first() }
+
+    pub fn sensors(&self) -> Vec<Rc<str>> {
+        let mut sensors: Vec<Rc<str>> = self.values.keys().cloned().collect();
+        sensors.sort();
+        sensors
+    }
+}
+
+pub type RecordFieldValues = (Rc<str>, DataType, Vec<DataTypeValue>);
+
+pub trait RecordField: Sized {
+    fn data_type() -> DataType;
+
+    fn values(&self) -> Vec<DataTypeValue>;
+
+    fn from_record_value(value: Option<&RecordValue>) -> Option<Self>;
+}
+
+macro_rules! impl_record_field {
+    ($($ty:ty => $data_type:ident, $cast:ident);* $(;)?) => {
+        $(
+            impl RecordField for $ty {
+                fn data_type() -> DataType { DataType::$data_type }
+
+                fn values(&self) -> Vec<DataTypeValue> { vec![self.clone().into()] }
+
+                fn from_record_value(value: Option<&RecordValue>) -> Option<Self> {
+                    value?.first()?.$cast().cloned()
+                }
+            }
+        )*
+    };
+}
+
+impl_record_field! {
+    bool => Bool, as_bool;
+    u8 => U8, as_u8;
+    u16 => U16, as_u16;
+    u32 => U32, as_u32;
+    u64 => U64, as_u64;
+    u128 => U128, as_u128;
+    usize => USize, as_u_size;
+    i8 => I8, as_i8;
+    i16 => I16, as_i16;
+    i32 => I32, as_i32;
+    i64 => I64, as_i64;
+    i128 => I128, as_i128;
+    isize => ISize, as_i_size;
+    f32 => F32, as_f32;
+    f64 => F64, as_f64;
+    Rc<str> => RcStr, as_rc_str;
+    String => String, as_string;
+}
+
+impl<T: RecordField> RecordField for Option<T> {
+    fn data_type() -> DataType { T::data_type() }
+
+    fn values(&self) -> Vec<DataTypeValue> {
+        match self {
+            Some(value) => value.values(),
+            None => Vec::new()
+        }
+    }
+
+    fn from_record_value(value: Option<&RecordValue>) -> Option<Self> {
+        match value {
+            Some(value) => Some(Some(T::from_record_value(Some(value))?)),
+            None => Some(None)
+        }
+    }
+}
+
+impl<T: RecordField> RecordField for Vec<T> {
+    fn data_type() -> DataType { T::data_type() }
+
+    fn values(&self) -> Vec<DataTypeValue> {
+        self.iter().flat_map(|value| value.values()).collect()
+    }
+
+    fn from_record_value(value: Option<&RecordValue>) -> Option<Self> {
+        let values = match value {
+            Some(value) => value.to_vec(),
+            None => return Some(Vec::new())
+        };
+        values.into_iter()
+            .map(|value| T::from_record_value(Some(&RecordValue::Single(value))))
+            .collect()
+    }
+}
+
+pub trait MagdsRecord: Sized {
+    fn parent_id() -> Rc<str>;
+
+    fn fields(&self) -> Vec<RecordFieldValues>;
+
+    fn from_values(record: &Record) -> Option<Self>;
+
+    fn insert_into(&self, magds: &mut MAGDS) -> Result<NeuronID, String> {
+        let parent_id = Self::parent_id();
+        let mut fields = Vec::new();
+
+        for (sensor_id, data_type, values) in self.fields() {
+            if let Some(sensor_data_type) = magds.sensor_data_type(sensor_id.clone()) {
+                if mem::discriminant(&sensor_data_type) != mem::discriminant(&data_type) {
+                    return Err(format!(
+                        "sensor {sensor_id} has data type {:?}, expected {:?}",
+                        sensor_data_type,
+                        data_type
+                    ))
+                }
+            }
+
+            let mut keyed = Vec::new();
+            for value in values {
+                let key = RowValue::new(&value)
+                    .ok_or(format!("sensor {sensor_id} has unsupported value {:?}", value))?;
+                keyed.push((key, value));
+            }
+            keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+            keyed.dedup_by(|(a, _), (b, _)| a == b);
+
+            let values = keyed.into_iter().map(|(_, value)| value).collect::<Vec<_>>();
+            fields.push((sensor_id, data_type, values));
+        }
+
+        for (sensor_id, data_type, _values) in &fields {
+            if magds.sensor_data_type(sensor_id.clone()).is_none() {
+                magds.create_sensor(sensor_id.clone(), data_type.clone())
+                    .ok_or(format!("cannot create sensor {sensor_id}"))?;
+            }
+        }
+
+        let mut number = magds.neurons.keys().filter(|id| id.parent_id == parent_id).count();
+        let neuron = loop {
+            number += 1;
+            let id = NeuronID { id: number.to_string().into(), parent_id: parent_id.clone() };
+            if !magds.neurons.contains_key(&id) {
+                break magds.create_neuron(id).ok_or("cannot create neuron".to_string())?
+            }
+        };
+
+        for (sensor_id, _data_type, values) in fields {
+            for value in values {
+                magds.sensor_insert_connected(sensor_id.clone(), &value, &neuron)?;
+            }
+        }
+
+        let id = neuron.borrow().id();
+        Ok(id)
+    }
+
+    fn from_record(magds: &MAGDS, id: &NeuronID) -> Option<Self> {
+        Self::from_values(&magds.record(id)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bionet_common::{
+        neuron::NeuronID,
+        data::DataType
+    };
+
+    use crate::simple::{ magds::MAGDS, parser };
+
+    use super::MagdsRecord;
+
+    #[derive(Debug, Clone, PartialEq, crate::MagdsRecord)]
+    #[magds(parent = "iris")]
+    struct Iris {
+        #[magds(sensor = "sepal.length")]
+        sepal_length: Option<f64>,
+        #[magds(sensor = "petal.length")]
+        petal_length: f64,
+        variety: String
+    }
+
+    #[derive(Debug, Clone, PartialEq, crate::MagdsRecord)]
+    struct Document {
+        title: String,
+        pages: u32,
+        tags: Vec<String>
+    }
+
+    #[derive(Debug, Clone, PartialEq, crate::MagdsRecord)]
+    struct Series {
+        label: String,
+        points: Vec<u32>
+    }
+
+    #[test]
+    fn derive_iris() {
+        let mut magds = parser::magds_from_csv("iris", "data/iris.csv").unwrap();
+        let iris = Iris::from_record(&magds, &NeuronID::new("1", "iris")).unwrap();
+        assert_eq!(iris.sepal_length, None);
+        assert_eq!(iris.petal_length, 1.4);
+        assert_eq!(iris.variety, "Setosa");
+
+        let new_iris = Iris {
+            sepal_length: Some(5.8), petal_length: 4.1, variety: "Versicolor".to_string()
+        };
+        let id = new_iris.insert_into(&mut magds).unwrap();
+        assert_eq!(&*id.id, "151");
+        assert_eq!(Iris::from_record(&magds, &id), Some(new_iris));
+
+        let sl58 = magds.sensor_search("sepal.length".into(), &5.8_f64.into()).unwrap();
+        assert_eq!(sl58.borrow().counter(), 8);
+    }
+
+    #[test]
+    fn derive_document() {
+        let mut magds = MAGDS::new();
+        let documents = vec![
+            Document {
+                title: "graphs".to_string(), pages: 12, tags: vec!["a".into(), "b".into()]
+            },
+            Document { title: "notes".to_string(), pages: 3, tags: Vec::new() }
+        ];
+
+        let ids: Vec<_> = documents.iter().map(|d| d.insert_into(&mut magds).unwrap()).collect();
+        assert_eq!(magds.neurons.len(), 2);
+        for (id, document) in ids.iter().zip(&documents) {
+            assert_eq!(&*id.parent_id, "Document");
+            assert_eq!(Document::from_record(&magds, id).as_ref(), Some(document));
+        }
+        let tag_a = magds.sensor_search("tags".into(), &"a".to_string().into()).unwrap();
+        assert_eq!(tag_a.borrow().counter(), 1);
+    }
+
+    #[test]
+    fn derive_series() {
+        let mut magds = MAGDS::new();
+        let series = Series { label: "a".to_string(), points: vec![2, 10] };
+        let id = series.insert_into(&mut magds).unwrap();
+        assert_eq!(Series::from_record(&magds, &id), Some(series));
+
+        let unsorted = Series { label: "b".to_string(), points: vec![10, 2] };
+        let id = unsorted.insert_into(&mut magds).unwrap();
+        let restored = Series::from_record(&magds, &id).unwrap();
+        assert_eq!(restored.points, vec![2, 10]);
+
+        let repeated = Series { label: "c".to_string(), points: vec![3, 3] };
+        let id = repeated.insert_into(&mut magds).unwrap();
+        assert_eq!(Series::from_record(&magds, &id).unwrap().points, vec![3]);
+        let point_3 = magds.sensor_search("points".into(), &3u32.into()).unwrap();
+        assert_eq!(point_3.borrow().counter(), 1);
+        assert_eq!(magds.neurons.len(), 3);
+
+        let mut magds = MAGDS::new();
+        magds.create_sensor("points".into(), DataType::String).unwrap();
+        let series = Series { label: "a".to_string(), points: vec![1] };
+        assert!(series.insert_into(&mut magds).is_err());
+        assert!(magds.sensor_data_type("label".into()).is_none());
+        assert!(magds.neurons.is_empty());
+    }
+}
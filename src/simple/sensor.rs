@@ -2,18 +2,26 @@ use std::{
     fmt::{ Display, Formatter, Result as FmtResult },
     rc::Rc,
     cell::RefCell,
-    collections::HashMap
+    collections::HashMap,
+    marker::PhantomData,
+    ops::Bound
 };
 
 use enum_as_inner::EnumAsInner;
 
 use bionet_common::{
-    neuron::{ Neuron, NeuronID },
-    sensor::Sensor,
-    data::{ DataType, DataTypeValue, DataCategory }
+    neuron::{ Neuron, NeuronID, NeuronConnectBilateral },
+    connection::ConnectionKind,
+    sensor::{ Sensor, SensorData },
+    data::{ DataType, DataTypeValue, DataCategory, DataDeductor }
 };
 
-use asa_graphs::neural::graph::ASAGraph;
+use asa_graphs::neural::{
+    graph::ASAGraph,
+    element::Element
+};
+
+use crate::neuron::simple_neuron::SimpleNeuron;
 
 #[derive(EnumAsInner)]
 pub enum SensorConatiner {
@@ -453,6 +461,436 @@ impl Sensor<DataTypeValue> for SensorConatiner {
     }
 }
 
+pub type SensorElement = (Rc<RefCell<dyn Neuron>>, HashMap<NeuronID, Rc<RefCell<dyn Neuron>>>);
+
+pub struct NearestElements {
+    pub elements: Vec<(DataTypeValue, SensorElement)>,
+    pub min: DataTypeValue,
+    pub max: DataTypeValue
+}
+
+impl SensorConatiner {
+    pub fn range(
+        &self, lower: Bound<&DataTypeValue>, upper: Bound<&DataTypeValue>
+    ) -> Result<Vec<SensorElement>, String> {
+        Ok(self.keyed_range(lower, upper)?.into_iter().map(|(_key, element)| element).collect())
+    }
+
+    pub fn keyed_range(
+        &self, lower: Bound<&DataTypeValue>, upper: Bound<&DataTypeValue>
+    ) -> Result<Vec<(DataTypeValue, SensorElement)>, String> {
+        let id = self.id();
+        match self {
+            SensorConatiner::Bool(v) => {
+                let lower = typed_bound(lower, DataTypeValue::as_bool, &id)?;
+                let upper = typed_bound(upper, DataTypeValue::as_bool, &id)?;
+                Ok(elements_in_range(v, lower, upper))
+            }
+            SensorConatiner::U8(v) => {
+                let lower = typed_bound(lower, DataTypeValue::as_u8, &id)?;
+                let upper = typed_bound(upper, DataTypeValue::as_u8, &id)?;
+                Ok(elements_in_range(v, lower, upper))
+            }
+            SensorConatiner::U16(v) => {
+                let lower = typed_bound(lower, DataTypeValue::as_u16, &id)?;
+                let upper = typed_bound(upper, DataTypeValue::as_u16, &id)?;
+                Ok(elements_in_range(v, lower, upper))
+            }
+            SensorConatiner::U32(v) => {
+                let lower = typed_bound(lower, DataTypeValue::as_u32, &id)?;
+                let upper = typed_bound(upper, DataTypeValue::as_u32, &id)?;
+                Ok(elements_in_range(v, lower, upper))
+            }
+            SensorConatiner::U64(v) => {
+                let lower = typed_bound(lower, DataTypeValue::as_u64, &id)?;
+                let upper = typed_bound(upper, DataTypeValue::as_u64, &id)?;
+                Ok(elements_in_range(v, lower, upper))
+            }
+            SensorConatiner::U128(v) => {
+                let lower = typed_bound(lower, DataTypeValue::as_u128, &id)?;
+                let upper = typed_bound(upper, DataTypeValue::as_u128, &id)?;
+                Ok(elements_in_range(v, lower, upper))
+            }
+            SensorConatiner::USize(v) => {
+                let lower = typed_bound(lower, DataTypeValue::as_u_size, &id)?;
+                let upper = typed_bound(upper, DataTypeValue::as_u_size, &id)?;
+                Ok(elements_in_range(v, lower, upper))
+            }
+            SensorConatiner::I8(v) => {
+                let lower = typed_bound(lower, DataTypeValue::as_i8, &id)?;
+                let upper = typed_bound(upper, DataTypeValue::as_i8, &id)?;
+                Ok(elements_in_range(v, lower, upper))
+            }
+            SensorConatiner::I16(v) => {
+                let lower = typed_bound(lower, DataTypeValue::as_i16, &id)?;
+                let upper = typed_bound(upper, DataTypeValue::as_i16, &id)?;
+                Ok(elements_in_range(v, lower, upper))
+            }
+            SensorConatiner::I32(v) => {
+                let lower = typed_bound(lower, DataTypeValue::as_i32, &id)?;
+                let upper = typed_bound(upper, DataTypeValue::as_i32, &id)?;
+                Ok(elements_in_range(v, lower, upper))
+            }
+            SensorConatiner::I64(v) => {
+                let lower = typed_bound(lower, DataTypeValue::as_i64, &id)?;
+                let upper = typed_bound(upper, DataTypeValue::as_i64, &id)?;
+                Ok(elements_in_range(v, lower, upper))
+            }
+            SensorConatiner::I128(v) => {
+                let lower = typed_bound(lower, DataTypeValue::as_i128, &id)?;
+                let upper = typed_bound(upper, DataTypeValue::as_i128, &id)?;
+                Ok(elements_in_range(v, lower, upper))
+            }
+            SensorConatiner::ISize(v) => {
+                let lower = typed_bound(lower, DataTypeValue::as_i_size, &id)?;
+                let upper = typed_bound(upper, DataTypeValue::as_i_size, &id)?;
+                Ok(elements_in_range(v, lower, upper))
+            }
+            SensorConatiner::F32(v) => {
+                let lower = typed_bound(lower, DataTypeValue::as_f32, &id)?;
+                let upper = typed_bound(upper, DataTypeValue::as_f32, &id)?;
+                Ok(elements_in_range(v, lower, upper))
+            }
+            SensorConatiner::F64(v) => {
+                let lower = typed_bound(lower, DataTypeValue::as_f64, &id)?;
+                let upper = typed_bound(upper, DataTypeValue::as_f64, &id)?;
+                Ok(elements_in_range(v, lower, upper))
+            }
+            SensorConatiner::RcStr(v) => {
+                let lower = typed_bound(lower, DataTypeValue::as_rc_str, &id)?;
+                let upper = typed_bound(upper, DataTypeValue::as_rc_str, &id)?;
+                Ok(elements_in_range(v, lower, upper))
+            }
+            SensorConatiner::String(v) => {
+                let lower = typed_bound(lower, DataTypeValue::as_string, &id)?;
+                let upper = typed_bound(upper, DataTypeValue::as_string, &id)?;
+                Ok(elements_in_range(v, lower, upper))
+            }
+        }
+    }
+
+    pub fn nearest(&self, item: &DataTypeValue) -> Result<Option<NearestElements>, String> {
+        let id = self.id();
+        let wrong_type = || format!("wrong data type {:?} for sensor {id}", item);
+        match self {
+            SensorConatiner::Bool(v) => {
+                Ok(nearest_elements(v, item.as_bool().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::U8(v) => {
+                Ok(nearest_elements(v, item.as_u8().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::U16(v) => {
+                Ok(nearest_elements(v, item.as_u16().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::U32(v) => {
+                Ok(nearest_elements(v, item.as_u32().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::U64(v) => {
+                Ok(nearest_elements(v, item.as_u64().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::U128(v) => {
+                Ok(nearest_elements(v, item.as_u128().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::USize(v) => {
+                Ok(nearest_elements(v, item.as_u_size().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::I8(v) => {
+                Ok(nearest_elements(v, item.as_i8().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::I16(v) => {
+                Ok(nearest_elements(v, item.as_i16().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::I32(v) => {
+                Ok(nearest_elements(v, item.as_i32().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::I64(v) => {
+                Ok(nearest_elements(v, item.as_i64().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::I128(v) => {
+                Ok(nearest_elements(v, item.as_i128().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::ISize(v) => {
+                Ok(nearest_elements(v, item.as_i_size().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::F32(v) => {
+                Ok(nearest_elements(v, item.as_f32().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::F64(v) => {
+                Ok(nearest_elements(v, item.as_f64().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::RcStr(v) => {
+                Ok(nearest_elements(v, item.as_rc_str().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::String(v) => {
+                Ok(nearest_elements(v, item.as_string().ok_or_else(wrong_type)?))
+            }
+        }
+    }
+
+    pub fn propagation_weights(
+        &self, item: &DataTypeValue
+    ) -> Result<Vec<(SensorElement, f32)>, String> {
+        let id = self.id();
+        let wrong_type = || format!("wrong data type {:?} for sensor {id}", item);
+        match self {
+            SensorConatiner::Bool(v) => {
+                Ok(propagation_weights(v, item.as_bool().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::U8(v) => {
+                Ok(propagation_weights(v, item.as_u8().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::U16(v) => {
+                Ok(propagation_weights(v, item.as_u16().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::U32(v) => {
+                Ok(propagation_weights(v, item.as_u32().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::U64(v) => {
+                Ok(propagation_weights(v, item.as_u64().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::U128(v) => {
+                Ok(propagation_weights(v, item.as_u128().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::USize(v) => {
+                Ok(propagation_weights(v, item.as_u_size().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::I8(v) => {
+                Ok(propagation_weights(v, item.as_i8().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::I16(v) => {
+                Ok(propagation_weights(v, item.as_i16().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::I32(v) => {
+                Ok(propagation_weights(v, item.as_i32().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::I64(v) => {
+                Ok(propagation_weights(v, item.as_i64().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::I128(v) => {
+                Ok(propagation_weights(v, item.as_i128().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::ISize(v) => {
+                Ok(propagation_weights(v, item.as_i_size().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::F32(v) => {
+                Ok(propagation_weights(v, item.as_f32().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::F64(v) => {
+                Ok(propagation_weights(v, item.as_f64().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::RcStr(v) => {
+                Ok(propagation_weights(v, item.as_rc_str().ok_or_else(wrong_type)?))
+            }
+            SensorConatiner::String(v) => {
+                Ok(propagation_weights(v, item.as_string().ok_or_else(wrong_type)?))
+            }
+        }
+    }
+
+    pub fn elements(&self) -> Vec<SensorElement> {
+        self.range(Bound::Unbounded, Bound::Unbounded).unwrap_or_default()
+    }
+
+    pub fn insert_connected(
+        &mut self, item: &DataTypeValue, neuron: &Rc<RefCell<SimpleNeuron>>
+    ) -> Result<Rc<RefCell<dyn Neuron>>, String> {
+        match self {
+            SensorConatiner::Bool(v) => insert_connected(v, item.as_bool(), neuron),
+            SensorConatiner::U8(v) => insert_connected(v, item.as_u8(), neuron),
+            SensorConatiner::U16(v) => insert_connected(v, item.as_u16(), neuron),
+            SensorConatiner::U32(v) => insert_connected(v, item.as_u32(), neuron),
+            SensorConatiner::U64(v) => insert_connected(v, item.as_u64(), neuron),
+            SensorConatiner::U128(v) => insert_connected(v, item.as_u128(), neuron),
+            SensorConatiner::USize(v) => insert_connected(v, item.as_u_size(), neuron),
+            SensorConatiner::I8(v) => insert_connected(v, item.as_i8(), neuron),
+            SensorConatiner::I16(v) => insert_connected(v, item.as_i16(), neuron),
+            SensorConatiner::I32(v) => insert_connected(v, item.as_i32(), neuron),
+            SensorConatiner::I64(v) => insert_connected(v, item.as_i64(), neuron),
+            SensorConatiner::I128(v) => insert_connected(v, item.as_i128(), neuron),
+            SensorConatiner::ISize(v) => insert_connected(v, item.as_i_size(), neuron),
+            SensorConatiner::F32(v) => insert_connected(v, item.as_f32(), neuron),
+            SensorConatiner::F64(v) => insert_connected(v, item.as_f64(), neuron),
+            SensorConatiner::RcStr(v) => insert_connected(v, item.as_rc_str(), neuron),
+            SensorConatiner::String(v) => insert_connected(v, item.as_string(), neuron),
+        }
+    }
+}
+
+fn typed_bound<'a, Key>(
+    bound: Bound<&'a DataTypeValue>, 
+    cast: fn(&'a DataTypeValue) -> Option<&'a Key>,
+    sensor_id: &str
+) -> Result<Bound<&'a Key>, String> {
+    let cast_item = |item: &'a DataTypeValue| {
+        cast(item).ok_or(format!("wrong data type {:?} for sensor {sensor_id}", item))
+    };
+    match bound {
+        Bound::Included(item) => Ok(Bound::Included(cast_item(item)?)),
+        Bound::Excluded(item) => Ok(Bound::Excluded(cast_item(item)?)),
+        Bound::Unbounded => Ok(Bound::Unbounded)
+    }
+}
+
+fn elements_in_range<Key>(
+    sensor: &ASAGraph<Key>, lower: Bound<&Key>, upper: Bound<&Key>
+) -> Vec<(DataTypeValue, SensorElement)>
+where 
+    Key: SensorData + PartialOrd, 
+    PhantomData<Key>: DataDeductor,
+    DataTypeValue: From<Key>
+{
+    let mut current = match lower {
+        Bound::Included(key) | Bound::Excluded(key) => {
+            floor_element(sensor, key).or(sensor.element_min.clone())
+        }
+        Bound::Unbounded => sensor.element_min.clone()
+    };
+
+    let mut elements: Vec<(DataTypeValue, SensorElement)> = Vec::new();
+    while let Some(element_ptr) = current {
+        let element = element_ptr.borrow();
+        let key = &element.key;
+        let above_lower = match lower {
+            Bound::Included(lower) => key >= lower,
+            Bound::Excluded(lower) => key > lower,
+            Bound::Unbounded => true
+        };
+        let below_upper = match upper {
+            Bound::Included(upper) => key <= upper,
+            Bound::Excluded(upper) => key < upper,
+            Bound::Unbounded => true
+        };
+        if !below_upper { break }
+        if above_lower {
+            elements.push((
+                DataTypeValue::from(key.clone()),
+                (element_ptr.clone() as Rc<RefCell<dyn Neuron>>, element.defined_neurons())
+            ));
+        }
+        current = element.next.as_ref().and_then(|(next, _weight)| next.upgrade());
+    }
+
+    elements
+}
+
+fn floor_element<Key, const ORDER: usize>(
+    sensor: &ASAGraph<Key, ORDER>, key: &Key
+) -> Option<Rc<RefCell<Element<Key, ORDER>>>>
+where 
+    Key: SensorData + PartialOrd, 
+    [(); ORDER + 1]:,
+    PhantomData<Key>: DataDeductor,
+    DataTypeValue: From<Key>
+{
+    let mut floor = None;
+    let mut node = Some(sensor.root.clone());
+    while let Some(node_ptr) = node {
+        let node_ref = node_ptr.borrow();
+        let (mut index, mut found) = (0, false);
+        while index < node_ref.size {
+            let element = match node_ref.keys[index].as_ref() {
+                Some(element) => element,
+                None => break
+            };
+            let element_ref = element.borrow();
+            if element_ref.key > *key { break }
+            floor = Some(element.clone());
+            if element_ref.key == *key { found = true; break }
+            index += 1;
+        }
+        node = if node_ref.is_leaf || found { None } else { node_ref.children[index].clone() };
+    }
+    floor
+}
+
+fn sensor_element<Key, const ORDER: usize>(
+    element_ptr: &Rc<RefCell<Element<Key, ORDER>>>
+) -> SensorElement
+where 
+    Key: SensorData, 
+    [(); ORDER + 1]:,
+    PhantomData<Key>: DataDeductor,
+    DataTypeValue: From<Key>
+{
+    let defined_neurons = element_ptr.borrow().defined_neurons();
+    (element_ptr.clone() as Rc<RefCell<dyn Neuron>>, defined_neurons)
+}
+
+fn nearest_elements<Key>(sensor: &ASAGraph<Key>, key: &Key) -> Option<NearestElements>
+where 
+    Key: SensorData + PartialOrd, 
+    PhantomData<Key>: DataDeductor,
+    DataTypeValue: From<Key>
+{
+    let min = DataTypeValue::from(sensor.element_min.as_ref()?.borrow().key.clone());
+    let max = DataTypeValue::from(sensor.element_max.as_ref()?.borrow().key.clone());
+
+    let lower = floor_element(sensor, key);
+    let upper = match &lower {
+        Some(element) if element.borrow().key == *key => None,
+        Some(element) => element.borrow().next.as_ref().and_then(|(next, _)| next.upgrade()),
+        None => sensor.element_min.clone()
+    };
+
+    let elements = lower.into_iter().chain(upper)
+        .map(|element_ptr| {
+            let key = DataTypeValue::from(element_ptr.borrow().key.clone());
+            (key, sensor_element(&element_ptr))
+        })
+        .collect();
+    Some(NearestElements { elements, min, max })
+}
+
+fn propagation_weights<Key>(sensor: &ASAGraph<Key>, key: &Key) -> Vec<(SensorElement, f32)>
+where 
+    Key: SensorData + PartialOrd, 
+    PhantomData<Key>: DataDeductor,
+    DataTypeValue: From<Key>
+{
+    let source = match sensor.search(key) {
+        Some(source) => source,
+        None => return Vec::new()
+    };
+    let mut weights: Vec<(SensorElement, f32)> = vec![(sensor_element(&source), 1.0f32)];
+    let (mut weight, mut current) = (1.0f32, source.borrow().prev.clone());
+    while let Some((element_ptr, edge_weight)) = current {
+        let element_ptr = match element_ptr.upgrade() {
+            Some(element_ptr) => element_ptr,
+            None => break
+        };
+        weight *= edge_weight;
+        if weight <= 0.0f32 { break }
+        weights.push((sensor_element(&element_ptr), weight));
+        current = element_ptr.borrow().prev.clone();
+    }
+    let (mut weight, mut current) = (1.0f32, source.borrow().next.clone());
+    while let Some((element_ptr, edge_weight)) = current {
+        let element_ptr = match element_ptr.upgrade() {
+            Some(element_ptr) => element_ptr,
+            None => break
+        };
+        weight *= edge_weight;
+        if weight <= 0.0f32 { break }
+        weights.push((sensor_element(&element_ptr), weight));
+        current = element_ptr.borrow().next.clone();
+    }
+    weights
+}
+
+fn insert_connected<Key>(
+    sensor: &mut ASAGraph<Key>, key: Option<&Key>, neuron: &Rc<RefCell<SimpleNeuron>>
+) -> Result<Rc<RefCell<dyn Neuron>>, String>
+where 
+    Key: SensorData, 
+    PhantomData<Key>: DataDeductor,
+    DataTypeValue: From<Key>
+{
+    let key = key.ok_or(format!("wrong data type for sensor {}", sensor.id()))?;
+    let element = sensor.insert(key);
+    neuron.borrow_mut().connect_bilateral_from(element.clone(), ConnectionKind::Defining)?;
+    Ok(element as Rc<RefCell<dyn Neuron>>)
+}
+
 impl From<ASAGraph<bool>> for SensorConatiner {
     fn from(sensor: ASAGraph<bool>) -> SensorConatiner {
         SensorConatiner::Bool(sensor)
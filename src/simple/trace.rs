@@ -0,0 +1,95 @@
+use std::{
+    collections::HashMap,
+    rc::Rc
+};
+
+use serde_json::{ json, Value };
+
+use bionet_common::neuron::NeuronID;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivationDirection {
+    Stimulus,
+    Horizontal,
+    Vertical
+}
+
+impl ActivationDirection {
+    fn name(&self) -> &'static str {
+        match self {
+            ActivationDirection::Stimulus => "stimulus",
+            ActivationDirection::Horizontal => "horizontal",
+            ActivationDirection::Vertical => "vertical"
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ActivationEvent {
+    pub step: usize,
+    pub sensor: Rc<str>,
+    pub source: NeuronID,
+    pub target: NeuronID,
+    pub signal: f32,
+    pub activation: f32,
+    pub direction: ActivationDirection
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SensorTraceSummary {
+    pub stimuli: usize,
+    pub horizontal: usize,
+    pub vertical: usize,
+    pub total_signal: f32,
+    pub max_activation: f32
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ActivationTrace {
+    pub events: Vec<ActivationEvent>,
+    pub(crate) steps: usize,
+    pub(crate) source: Option<NeuronID>
+}
+
+impl ActivationTrace {
+    pub fn new() -> ActivationTrace { ActivationTrace::default() }
+
+    pub fn steps(&self) -> usize { self.steps }
+
+    pub(crate) fn next_step(&mut self) -> usize {
+        self.steps += 1;
+        self.steps
+    }
+
+    pub fn summary(&self) -> HashMap<Rc<str>, SensorTraceSummary> {
+        let mut summary: HashMap<Rc<str>, SensorTraceSummary> = HashMap::new();
+        for event in &self.events {
+            let sensor = summary.entry(event.sensor.clone()).or_default();
+            match event.direction {
+                ActivationDirection::Stimulus => sensor.stimuli += 1,
+                ActivationDirection::Horizontal => sensor.horizontal += 1,
+                ActivationDirection::Vertical => sensor.vertical += 1
+            }
+            sensor.total_signal += event.signal;
+            sensor.max_activation = sensor.max_activation.max(event.activation);
+        }
+        summary
+    }
+
+    pub fn to_json(&self) -> Value {
+        let events: Vec<Value> = self.events.iter()
+            .map(|event| json!({
+                "step": event.step,
+                "sensor": &*event.sensor,
+                "source": { "id": &*event.source.id, "parent_id": &*event.source.parent_id },
+                "target": { "id": &*event.target.id, "parent_id": &*event.target.parent_id },
+                "signal": event.signal,
+                "activation": event.activation,
+                "direction": event.direction.name()
+            }))
+            .collect();
+        json!({ "steps": self.steps, "events": events })
+    }
+
+    pub fn to_json_string(&self) -> String { self.to_json().to_string() }
+}